@@ -13,11 +13,18 @@ use cssparser::RGBA;
 use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
 use std::marker::PhantomData;
 
-pub struct RaqoteBackend;
+#[derive(Default)]
+pub struct RaqoteBackend {
+    /// Backing pixel buffer for the most recently set `Source::Image` fill
+    /// style. Replacing it (instead of leaking a fresh allocation on every
+    /// `set_fill_style` call) bounds the backend to at most one extra buffer
+    /// rather than growing unboundedly across repeated pattern fills.
+    fill_style_surface_data: Option<Box<[u32]>>,
+}
 
 impl Backend for RaqoteBackend {
-    fn get_composition_op(&self, _opts: &DrawOptions) -> CompositionOp {
-        unimplemented!()
+    fn get_composition_op(&self, opts: &DrawOptions) -> CompositionOp {
+        CompositionOp::Raqote(opts.as_raqote().blend_mode)
     }
 
     fn need_to_draw_shadow(&self, color: &Color) -> bool {
@@ -34,8 +41,13 @@ impl Backend for RaqoteBackend {
         }
     }
 
-    fn set_shadow_color<'a>(&mut self, _color: RGBA, _state: &mut CanvasPaintState<'a>) {
-        unimplemented!()
+    fn set_shadow_color<'a>(&mut self, color: RGBA, state: &mut CanvasPaintState<'a>) {
+        state.shadow_color = Color::Raqote(raqote::SolidSource {
+            r: color.red,
+            g: color.green,
+            b: color.blue,
+            a: color.alpha,
+        });
     }
 
     fn set_fill_style<'a>(
@@ -44,7 +56,7 @@ impl Backend for RaqoteBackend {
         state: &mut CanvasPaintState<'a>,
         _drawtarget: &dyn GenericDrawTarget,
     ) {
-        if let Some(source) = style.to_raqote_source() {
+        if let Some(source) = style.to_raqote_source(&mut self.fill_style_surface_data) {
             state.fill_style = Pattern::Raqote(source);
         }
     }
@@ -60,10 +72,10 @@ impl Backend for RaqoteBackend {
 
     fn set_global_composition<'a>(
         &mut self,
-        _op: CompositionOrBlending,
-        _state: &mut CanvasPaintState<'a>,
+        op: CompositionOrBlending,
+        state: &mut CanvasPaintState<'a>,
     ) {
-        unimplemented!()
+        state.draw_options.set_blend_mode(op.to_raqote_style());
     }
 
     fn create_drawtarget(&self, size: Size2D<u64>) -> Box<dyn GenericDrawTarget> {
@@ -148,6 +160,16 @@ impl<'a> StrokeOptions<'a> {
             StrokeOptions::Raqote(options, _) => options.cap = _val.to_raqote_style(),
         }
     }
+    pub fn set_line_dash(&mut self, segments: &[f32]) {
+        match self {
+            StrokeOptions::Raqote(options, _) => options.dash_array = segments.to_vec(),
+        }
+    }
+    pub fn set_line_dash_offset(&mut self, offset: f32) {
+        match self {
+            StrokeOptions::Raqote(options, _) => options.dash_offset = offset,
+        }
+    }
     pub fn as_raqote(&self) -> &raqote::StrokeStyle {
         match self {
             StrokeOptions::Raqote(options, _) => options,
@@ -161,6 +183,11 @@ impl DrawOptions {
             DrawOptions::Raqote(draw_options) => draw_options.alpha = _val,
         }
     }
+    pub fn set_blend_mode(&mut self, val: raqote::BlendMode) {
+        match self {
+            DrawOptions::Raqote(draw_options) => draw_options.blend_mode = val,
+        }
+    }
     pub fn as_raqote(&self) -> &raqote::DrawOptions {
         match self {
             DrawOptions::Raqote(options) => options,
@@ -171,17 +198,74 @@ impl DrawOptions {
 impl Path {
     pub fn transformed_copy_to_builder(
         &self,
-        _transform: &Transform2D<f32>,
+        transform: &Transform2D<f32>,
     ) -> Box<dyn GenericPathBuilder> {
-        unimplemented!()
+        let mut builder = PathBuilder::new();
+        let tp = |p: raqote::Point| transform.transform_point(Point2D::new(p.x, p.y));
+        for op in &self.as_raqote().ops {
+            match op {
+                raqote::PathOp::MoveTo(p) => builder.move_to(tp(*p)),
+                raqote::PathOp::LineTo(p) => builder.line_to(tp(*p)),
+                raqote::PathOp::QuadTo(c, p) => {
+                    builder.quadratic_curve_to(&tp(*c), &tp(*p))
+                },
+                raqote::PathOp::CubicTo(c1, c2, p) => {
+                    builder.bezier_curve_to(&tp(*c1), &tp(*c2), &tp(*p))
+                },
+                raqote::PathOp::Close => builder.close(),
+            }
+        }
+        Box::new(builder)
     }
 
-    pub fn contains_point(&self, _x: f64, _y: f64, _path_transform: &Transform2D<f32>) -> bool {
-        unimplemented!()
+    pub fn contains_point(&self, x: f64, y: f64, path_transform: &Transform2D<f32>) -> bool {
+        // Hit-testing happens in path space, so move the query point there first.
+        let point = match path_transform.inverse() {
+            Some(inverse) => inverse.transform_point(Point2D::new(x as f32, y as f32)),
+            None => return false,
+        };
+        // Cast a ray to the right and count crossings of the flattened edges; an
+        // odd count means the point is inside (even-odd rule).
+        let mut inside = false;
+        let mut start = Point2D::zero();
+        let mut last = Point2D::zero();
+        let mut cross = |a: Point2D<f32>, b: Point2D<f32>| {
+            if (a.y > point.y) != (b.y > point.y) {
+                let t = (point.y - a.y) / (b.y - a.y);
+                if point.x < a.x + t * (b.x - a.x) {
+                    inside = !inside;
+                }
+            }
+        };
+        for op in &self.as_raqote().ops {
+            match op {
+                raqote::PathOp::MoveTo(p) => {
+                    start = Point2D::new(p.x, p.y);
+                    last = start;
+                },
+                raqote::PathOp::LineTo(p) => {
+                    let p = Point2D::new(p.x, p.y);
+                    cross(last, p);
+                    last = p;
+                },
+                raqote::PathOp::QuadTo(_, p) | raqote::PathOp::CubicTo(_, _, p) => {
+                    // Treat curve segments as straight chords for hit-testing.
+                    let p = Point2D::new(p.x, p.y);
+                    cross(last, p);
+                    last = p;
+                },
+                raqote::PathOp::Close => {
+                    cross(last, start);
+                    last = start;
+                },
+            }
+        }
+        cross(last, start);
+        inside
     }
 
     pub fn copy_to_builder(&self) -> Box<dyn GenericPathBuilder> {
-        unimplemented!()
+        self.transformed_copy_to_builder(&Transform2D::identity())
     }
 
     pub fn as_raqote(&self) -> &raqote::Path {
@@ -209,62 +293,149 @@ impl GenericDrawTarget for raqote::DrawTarget {
                 b: 0,
                 a: 0,
             }),
-            &raqote::DrawOptions::new(),
+            &raqote::DrawOptions {
+                blend_mode: raqote::BlendMode::Clear,
+                ..Default::default()
+            },
         );
     }
     fn copy_surface(
         &mut self,
-        _surface: SourceSurface,
-        _source: Rect<i32>,
-        _destination: Point2D<i32>,
+        surface: SourceSurface,
+        source: Rect<i32>,
+        destination: Point2D<i32>,
     ) {
-        unimplemented!();
+        let mut dt = raqote::DrawTarget::new(source.size.width, source.size.height);
+        dt.get_data_mut()
+            .copy_from_slice(&pixels_to_u32(&surface));
+        raqote::DrawTarget::copy_surface(self, &dt, source.to_box2d(), destination);
     }
     fn create_gradient_stops(
         &self,
-        _gradient_stops: Vec<GradientStop>,
+        gradient_stops: Vec<GradientStop>,
         _extend_mode: ExtendMode,
     ) -> GradientStops {
-        unimplemented!();
+        let mut stops = gradient_stops;
+        stops.sort_by(|a, b| a.position.partial_cmp(&b.position).unwrap());
+        stops
     }
     fn create_path_builder(&self) -> Box<dyn GenericPathBuilder> {
         Box::new(PathBuilder::new())
     }
     fn create_similar_draw_target(
         &self,
-        _size: &Size2D<i32>,
+        size: &Size2D<i32>,
         _format: SurfaceFormat,
     ) -> Box<dyn GenericDrawTarget> {
-        unimplemented!();
+        Box::new(raqote::DrawTarget::new(size.width, size.height))
     }
     fn create_source_surface_from_data(
         &self,
-        _data: &[u8],
+        data: &[u8],
         _size: Size2D<i32>,
         _stride: i32,
     ) -> Option<SourceSurface> {
-        unimplemented!();
+        // The incoming bytes are premultiplied BGRA; the raqote `Image` that
+        // eventually wraps them is built lazily in `draw_surface`, so just keep
+        // an owned copy here.
+        Some(data.to_vec())
     }
     fn draw_surface(
-        &self,
-        _surface: SourceSurface,
-        _dest: Rect<f64>,
-        _source: Rect<f64>,
-        _filter: Filter,
-        _draw_options: &DrawOptions,
+        &mut self,
+        surface: SourceSurface,
+        dest: Rect<f64>,
+        source: Rect<f64>,
+        filter: Filter,
+        draw_options: &DrawOptions,
     ) {
-        unimplemented!();
+        let data = pixels_to_u32(&surface);
+        let image = raqote::Image {
+            width: source.size.width as i32,
+            height: source.size.height as i32,
+            data: &data,
+        };
+        // Map the destination onto the source so an arbitrary dest rect scales
+        // the image rather than clipping it, then paint through a `Source::Image`
+        // so the requested `Filter` is honored.
+        let scale = Transform2D::scale(
+            (dest.size.width / source.size.width) as f32,
+            (dest.size.height / source.size.height) as f32,
+        );
+        let transform = scale
+            .then_translate(Vector2D::new(dest.origin.x as f32, dest.origin.y as f32))
+            .inverse()
+            .unwrap_or_else(Transform2D::identity);
+        let source = raqote::Source::Image(
+            image,
+            raqote::ExtendMode::Pad,
+            filter.to_raqote_filter(),
+            transform,
+        );
+        let mut pb = raqote::PathBuilder::new();
+        pb.rect(
+            dest.origin.x as f32,
+            dest.origin.y as f32,
+            dest.size.width as f32,
+            dest.size.height as f32,
+        );
+        raqote::DrawTarget::fill(self, &pb.finish(), &source, draw_options.as_raqote());
     }
     fn draw_surface_with_shadow(
-        &self,
-        _surface: SourceSurface,
-        _dest: &Point2D<f32>,
-        _color: &Color,
-        _offset: &Vector2D<f32>,
-        _sigma: f32,
-        _operator: CompositionOp,
+        &mut self,
+        surface: SourceSurface,
+        dest: &Point2D<f32>,
+        color: &Color,
+        offset: &Vector2D<f32>,
+        sigma: f32,
+        operator: CompositionOp,
     ) {
-        unimplemented!();
+        let size = self.get_size();
+        let (width, height) = (size.width as usize, size.height as usize);
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Tint the rendered shape with the shadow color, keeping its coverage
+        // (alpha) and replacing the RGB with the shadow's premultiplied color.
+        let shadow = color.as_raqote();
+        let mut pixels = pixels_to_u32(&surface);
+        for pixel in pixels.iter_mut() {
+            let a = (*pixel >> 24) as u8;
+            let sa = a as u32 * shadow.a as u32 / 255;
+            let premul = |c: u8| (c as u32 * sa / 255) as u32;
+            *pixel = (sa << 24) |
+                (premul(shadow.r) << 16) |
+                (premul(shadow.g) << 8) |
+                premul(shadow.b);
+        }
+
+        // Separable Gaussian blur; `sigma <= 0` means a hard-edged shadow so we
+        // just offset-copy the tinted buffer.
+        if sigma > 0. {
+            gaussian_blur(&mut pixels, width, height, sigma);
+        }
+
+        let shadow_target = {
+            let mut dt = raqote::DrawTarget::new(size.width, size.height);
+            dt.get_data_mut().copy_from_slice(&pixels);
+            dt
+        };
+        let image = raqote::Image {
+            width: size.width,
+            height: size.height,
+            data: shadow_target.get_data(),
+        };
+        let CompositionOp::Raqote(blend_mode) = operator;
+        raqote::DrawTarget::draw_image_at(
+            self,
+            dest.x + offset.x,
+            dest.y + offset.y,
+            &image,
+            &raqote::DrawOptions {
+                blend_mode,
+                ..Default::default()
+            },
+        );
     }
     fn fill(&mut self, path: &Path, pattern: Pattern, draw_options: &DrawOptions) {
         self.fill(
@@ -277,7 +448,7 @@ impl GenericDrawTarget for raqote::DrawTarget {
         &mut self,
         rect: &Rect<f32>,
         pattern: Pattern,
-        _draw_options: Option<&DrawOptions>,
+        draw_options: Option<&DrawOptions>,
     ) {
         let mut pb = raqote::PathBuilder::new();
         pb.rect(
@@ -286,18 +457,18 @@ impl GenericDrawTarget for raqote::DrawTarget {
             rect.size.width,
             rect.size.height,
         );
-        raqote::DrawTarget::fill(
-            self,
-            &pb.finish(),
-            pattern.as_raqote(),
-            &raqote::DrawOptions::new(),
-        );
+        // Honor the caller's blend mode / alpha when one is supplied so that
+        // `globalCompositeOperation` takes effect for rect fills.
+        let draw_options = draw_options
+            .map(|options| *options.as_raqote())
+            .unwrap_or_default();
+        raqote::DrawTarget::fill(self, &pb.finish(), pattern.as_raqote(), &draw_options);
     }
     fn get_format(&self) -> SurfaceFormat {
-        unimplemented!();
+        SurfaceFormat::Raqote(())
     }
     fn get_size(&self) -> Size2D<i32> {
-        unimplemented!();
+        Size2D::new(self.width(), self.height())
     }
     fn get_transform(&self) -> Transform2D<f32> {
         *self.get_transform()
@@ -312,7 +483,7 @@ impl GenericDrawTarget for raqote::DrawTarget {
         self.set_transform(matrix);
     }
     fn snapshot(&self) -> SourceSurface {
-        unimplemented!();
+        self.snapshot_data(&|bytes| bytes.to_vec())
     }
     fn stroke(
         &mut self,
@@ -330,22 +501,44 @@ impl GenericDrawTarget for raqote::DrawTarget {
     }
     fn stroke_line(
         &mut self,
-        _start: Point2D<f32>,
-        _end: Point2D<f32>,
-        _pattern: Pattern,
-        _stroke_options: &StrokeOptions,
-        _draw_options: &DrawOptions,
+        start: Point2D<f32>,
+        end: Point2D<f32>,
+        pattern: Pattern,
+        stroke_options: &StrokeOptions,
+        draw_options: &DrawOptions,
     ) {
-        unimplemented!();
+        let mut pb = raqote::PathBuilder::new();
+        pb.move_to(start.x, start.y);
+        pb.line_to(end.x, end.y);
+        raqote::DrawTarget::stroke(
+            self,
+            &pb.finish(),
+            pattern.as_raqote(),
+            stroke_options.as_raqote(),
+            draw_options.as_raqote(),
+        );
     }
     fn stroke_rect(
         &mut self,
-        _rect: &Rect<f32>,
-        _pattern: Pattern,
-        _stroke_options: &StrokeOptions,
-        _draw_options: &DrawOptions,
+        rect: &Rect<f32>,
+        pattern: Pattern,
+        stroke_options: &StrokeOptions,
+        draw_options: &DrawOptions,
     ) {
-        unimplemented!();
+        let mut pb = raqote::PathBuilder::new();
+        pb.rect(
+            rect.origin.x,
+            rect.origin.y,
+            rect.size.width,
+            rect.size.height,
+        );
+        raqote::DrawTarget::stroke(
+            self,
+            &pb.finish(),
+            pattern.as_raqote(),
+            stroke_options.as_raqote(),
+            draw_options.as_raqote(),
+        );
     }
     #[allow(unsafe_code)]
     fn snapshot_data(&self, f: &dyn Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
@@ -362,11 +555,18 @@ impl GenericDrawTarget for raqote::DrawTarget {
     }
 }
 
-struct PathBuilder(Option<raqote::PathBuilder>);
+pub(crate) struct PathBuilder {
+    builder: Option<raqote::PathBuilder>,
+    /// Last emitted point, so `get_current_point` can answer without a raqote hook.
+    current: Point2D<f32>,
+}
 
 impl PathBuilder {
-    fn new() -> PathBuilder {
-        PathBuilder(Some(raqote::PathBuilder::new()))
+    pub(crate) fn new() -> PathBuilder {
+        PathBuilder {
+            builder: Some(raqote::PathBuilder::new()),
+            current: Point2D::zero(),
+        }
     }
 }
 
@@ -379,10 +579,12 @@ impl GenericPathBuilder for PathBuilder {
         end_angle: f32,
         _anticlockwise: bool,
     ) {
-        self.0
+        self.builder
             .as_mut()
             .unwrap()
             .arc(origin.x, origin.y, radius, start_angle, end_angle);
+        self.current = origin +
+            Vector2D::new(radius * end_angle.cos(), radius * end_angle.sin());
     }
     fn bezier_curve_to(
         &mut self,
@@ -390,7 +592,7 @@ impl GenericPathBuilder for PathBuilder {
         control_point2: &Point2D<f32>,
         control_point3: &Point2D<f32>,
     ) {
-        self.0.as_mut().unwrap().cubic_to(
+        self.builder.as_mut().unwrap().cubic_to(
             control_point1.x,
             control_point1.y,
             control_point2.x,
@@ -398,41 +600,87 @@ impl GenericPathBuilder for PathBuilder {
             control_point3.x,
             control_point3.y,
         );
+        self.current = *control_point3;
     }
     fn close(&mut self) {
-        self.0.as_mut().unwrap().close();
+        self.builder.as_mut().unwrap().close();
     }
     fn ellipse(
         &mut self,
-        _origin: Point2D<f32>,
-        _radius_x: f32,
-        _radius_y: f32,
-        _rotation_angle: f32,
-        _start_angle: f32,
-        _end_angle: f32,
-        _anticlockwise: bool,
+        origin: Point2D<f32>,
+        radius_x: f32,
+        radius_y: f32,
+        rotation_angle: f32,
+        start_angle: f32,
+        end_angle: f32,
+        anticlockwise: bool,
     ) {
-        unimplemented!();
+        // Approximate the ellipse arc by up to four quarter segments, each a cubic
+        // Bézier with the standard 4/3 * tan(theta/4) control-point factor (0.5523
+        // for a full quarter), then rotate every point by `rotation_angle`.
+        let mut angle = start_angle;
+        let end = end_angle;
+        let sweep = if anticlockwise {
+            -(start_angle - end).rem_euclid(2.0 * std::f32::consts::PI)
+        } else {
+            (end - start_angle).rem_euclid(2.0 * std::f32::consts::PI)
+        };
+        let (sin_r, cos_r) = rotation_angle.sin_cos();
+        let to_point = |a: f32| {
+            let (s, c) = a.sin_cos();
+            let x = radius_x * c;
+            let y = radius_y * s;
+            Point2D::new(
+                origin.x + x * cos_r - y * sin_r,
+                origin.y + x * sin_r + y * cos_r,
+            )
+        };
+
+        self.line_to(to_point(start_angle));
+        let segments = (sweep.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as i32;
+        let delta = sweep / segments as f32;
+        let alpha = 4.0 / 3.0 * (delta / 4.0).tan();
+        for _ in 0..segments {
+            let next = angle + delta;
+            let (sin1, cos1) = angle.sin_cos();
+            let (sin2, cos2) = next.sin_cos();
+            // Control points in unit-circle space, scaled by the radii.
+            let c1 = Point2D::new(cos1 - alpha * sin1, sin1 + alpha * cos1);
+            let c2 = Point2D::new(cos2 + alpha * sin2, sin2 - alpha * cos2);
+            let scale_rotate = |p: Point2D<f32>| {
+                let x = radius_x * p.x;
+                let y = radius_y * p.y;
+                Point2D::new(
+                    origin.x + x * cos_r - y * sin_r,
+                    origin.y + x * sin_r + y * cos_r,
+                )
+            };
+            self.bezier_curve_to(&scale_rotate(c1), &scale_rotate(c2), &to_point(next));
+            angle = next;
+        }
     }
     fn get_current_point(&mut self) -> Point2D<f32> {
-        unimplemented!();
+        self.current
     }
     fn line_to(&mut self, point: Point2D<f32>) {
-        self.0.as_mut().unwrap().line_to(point.x, point.y);
+        self.builder.as_mut().unwrap().line_to(point.x, point.y);
+        self.current = point;
     }
     fn move_to(&mut self, point: Point2D<f32>) {
-        self.0.as_mut().unwrap().move_to(point.x, point.y);
+        self.builder.as_mut().unwrap().move_to(point.x, point.y);
+        self.current = point;
     }
     fn quadratic_curve_to(&mut self, control_point: &Point2D<f32>, end_point: &Point2D<f32>) {
-        self.0.as_mut().unwrap().quad_to(
+        self.builder.as_mut().unwrap().quad_to(
             control_point.x,
             control_point.y,
             end_point.x,
             end_point.y,
         );
+        self.current = *end_point;
     }
     fn finish(&mut self) -> Path {
-        Path::Raqote(self.0.take().unwrap().finish())
+        Path::Raqote(self.builder.take().unwrap().finish())
     }
 }
 
@@ -442,6 +690,62 @@ pub trait ToRaqoteStyle {
     fn to_raqote_style(self) -> Self::Target;
 }
 
+impl ToRaqoteStyle for CompositionOrBlending {
+    type Target = raqote::BlendMode;
+
+    fn to_raqote_style(self) -> raqote::BlendMode {
+        match self {
+            CompositionOrBlending::Composition(op) => op.to_raqote_style(),
+            CompositionOrBlending::Blending(op) => op.to_raqote_style(),
+        }
+    }
+}
+
+impl ToRaqoteStyle for CompositionStyle {
+    type Target = raqote::BlendMode;
+
+    fn to_raqote_style(self) -> raqote::BlendMode {
+        match self {
+            CompositionStyle::SrcIn => raqote::BlendMode::SrcIn,
+            CompositionStyle::SrcOut => raqote::BlendMode::SrcOut,
+            CompositionStyle::SrcOver => raqote::BlendMode::SrcOver,
+            CompositionStyle::SrcAtop => raqote::BlendMode::SrcAtop,
+            CompositionStyle::DestIn => raqote::BlendMode::DstIn,
+            CompositionStyle::DestOut => raqote::BlendMode::DstOut,
+            CompositionStyle::DestOver => raqote::BlendMode::DstOver,
+            CompositionStyle::DestAtop => raqote::BlendMode::DstAtop,
+            CompositionStyle::Copy => raqote::BlendMode::Src,
+            CompositionStyle::Lighter => raqote::BlendMode::Add,
+            CompositionStyle::Xor => raqote::BlendMode::Xor,
+            CompositionStyle::Clear => raqote::BlendMode::Clear,
+        }
+    }
+}
+
+impl ToRaqoteStyle for BlendingStyle {
+    type Target = raqote::BlendMode;
+
+    fn to_raqote_style(self) -> raqote::BlendMode {
+        match self {
+            BlendingStyle::Multiply => raqote::BlendMode::Multiply,
+            BlendingStyle::Screen => raqote::BlendMode::Screen,
+            BlendingStyle::Overlay => raqote::BlendMode::Overlay,
+            BlendingStyle::Darken => raqote::BlendMode::Darken,
+            BlendingStyle::Lighten => raqote::BlendMode::Lighten,
+            BlendingStyle::ColorDodge => raqote::BlendMode::ColorDodge,
+            BlendingStyle::ColorBurn => raqote::BlendMode::ColorBurn,
+            BlendingStyle::HardLight => raqote::BlendMode::HardLight,
+            BlendingStyle::SoftLight => raqote::BlendMode::SoftLight,
+            BlendingStyle::Difference => raqote::BlendMode::Difference,
+            BlendingStyle::Exclusion => raqote::BlendMode::Exclusion,
+            BlendingStyle::Hue => raqote::BlendMode::Hue,
+            BlendingStyle::Saturation => raqote::BlendMode::Saturation,
+            BlendingStyle::Color => raqote::BlendMode::Color,
+            BlendingStyle::Luminosity => raqote::BlendMode::Luminosity,
+        }
+    }
+}
+
 impl ToRaqoteStyle for LineJoinStyle {
     type Target = raqote::LineJoin;
 
@@ -466,20 +770,51 @@ impl ToRaqoteStyle for LineCapStyle {
     }
 }
 
-// TODO(pylbrecht)
 #[cfg(feature = "raqote_backend")]
 impl Clone for Pattern<'_> {
     fn clone(&self) -> Self {
-        unimplemented!();
+        let source = match self.as_raqote() {
+            raqote::Source::Solid(solid) => raqote::Source::Solid(*solid),
+            raqote::Source::LinearGradient(gradient, spread, transform) => {
+                raqote::Source::LinearGradient(gradient.clone(), *spread, *transform)
+            },
+            raqote::Source::RadialGradient(gradient, spread, transform) => {
+                raqote::Source::RadialGradient(gradient.clone(), *spread, *transform)
+            },
+            raqote::Source::TwoCircleRadialGradient(gradient, spread, c1, r1, c2, r2, transform) => {
+                raqote::Source::TwoCircleRadialGradient(
+                    gradient.clone(),
+                    *spread,
+                    *c1,
+                    *r1,
+                    *c2,
+                    *r2,
+                    *transform,
+                )
+            },
+            raqote::Source::Image(image, extend, filter, transform) => {
+                raqote::Source::Image(image.clone(), *extend, *filter, *transform)
+            },
+        };
+        Pattern::Raqote(source)
     }
 }
 
 pub trait ToRaqoteSource<'a> {
-    fn to_raqote_source(self) -> Option<raqote::Source<'a>>;
+    /// `surface_data` is the backend's single-slot buffer for a `Surface` fill
+    /// style's pixels: populated (replacing whatever the previous fill style
+    /// left there) instead of leaking a fresh allocation on every call.
+    fn to_raqote_source(
+        self,
+        surface_data: &'a mut Option<Box<[u32]>>,
+    ) -> Option<raqote::Source<'a>>;
 }
 
 impl<'a> ToRaqoteSource<'a> for FillOrStrokeStyle {
-    fn to_raqote_source(self) -> Option<raqote::Source<'a>> {
+    fn to_raqote_source(
+        self,
+        surface_data: &'a mut Option<Box<[u32]>>,
+    ) -> Option<raqote::Source<'a>> {
         use canvas_traits::canvas::FillOrStrokeStyle::*;
 
         match self {
@@ -489,13 +824,194 @@ impl<'a> ToRaqoteSource<'a> for FillOrStrokeStyle {
                 b: rgba.blue,
                 a: rgba.alpha,
             })),
-            LinearGradient(_) => unimplemented!(),
-            RadialGradient(_) => unimplemented!(),
-            Surface(_) => unimplemented!(),
+            LinearGradient(style) => {
+                let stops = style
+                    .stops
+                    .into_iter()
+                    .map(|stop| raqote::GradientStop {
+                        position: stop.offset as f32,
+                        color: raqote::Color::new(
+                            stop.color.alpha,
+                            stop.color.red,
+                            stop.color.green,
+                            stop.color.blue,
+                        ),
+                    })
+                    .collect::<Vec<raqote::GradientStop>>();
+                // `is_zero_size_gradient` treats an empty stop list as a no-op, so
+                // never build a gradient source in that case.
+                if stops.is_empty() {
+                    return None;
+                }
+                Some(raqote::Source::new_linear_gradient(
+                    raqote::Gradient { stops },
+                    raqote::Point::new(style.x0 as f32, style.y0 as f32),
+                    raqote::Point::new(style.x1 as f32, style.y1 as f32),
+                    raqote::Spread::Pad,
+                ))
+            },
+            RadialGradient(style) => {
+                let stops = style
+                    .stops
+                    .into_iter()
+                    .map(|stop| raqote::GradientStop {
+                        position: stop.offset as f32,
+                        color: raqote::Color::new(
+                            stop.color.alpha,
+                            stop.color.red,
+                            stop.color.green,
+                            stop.color.blue,
+                        ),
+                    })
+                    .collect::<Vec<raqote::GradientStop>>();
+                if stops.is_empty() {
+                    return None;
+                }
+                let gradient = raqote::Gradient { stops };
+                let center0 = raqote::Point::new(style.x0 as f32, style.y0 as f32);
+                let center1 = raqote::Point::new(style.x1 as f32, style.y1 as f32);
+                // A single-circle gradient is the common case; fall back to the
+                // two-circle form only when the circles actually differ.
+                if center0 == center1 && style.r0 == 0. {
+                    Some(raqote::Source::new_radial_gradient(
+                        gradient,
+                        center1,
+                        style.r1 as f32,
+                        raqote::Spread::Pad,
+                    ))
+                } else {
+                    Some(raqote::Source::new_two_circle_radial_gradient(
+                        gradient,
+                        center0,
+                        style.r0 as f32,
+                        center1,
+                        style.r1 as f32,
+                        raqote::Spread::Pad,
+                    ))
+                }
+            },
+            Surface(ref style) => {
+                let pixels = pixels_to_u32(&style.surface_data).into_boxed_slice();
+                // raqote's `Image` borrows its pixels, and the source outlives this
+                // call, so stash the owned buffer in the caller-provided slot rather
+                // than leaking it.
+                let data: &'a [u32] = surface_data.insert(pixels);
+                let image = raqote::Image {
+                    width: style.surface_size.width as i32,
+                    height: style.surface_size.height as i32,
+                    data,
+                };
+                Some(raqote::Source::Image(
+                    image,
+                    raqote::ExtendMode::Repeat,
+                    raqote::FilterMode::Bilinear,
+                    raqote::Transform::identity(),
+                ))
+            },
+        }
+    }
+}
+
+impl Filter {
+    fn to_raqote_filter(self) -> raqote::FilterMode {
+        match self {
+            Filter::Bilinear => raqote::FilterMode::Bilinear,
+            Filter::Nearest => raqote::FilterMode::Nearest,
+        }
+    }
+}
+
+/// Apply a separable Gaussian blur in place over a premultiplied ARGB `u32`
+/// buffer. The kernel radius is `ceil(3 * sigma)` and weights are sampled from
+/// `exp(-x^2 / (2 * sigma^2))`, normalized to sum 1; edge samples are clamped.
+fn gaussian_blur(pixels: &mut [u32], width: usize, height: usize, sigma: f32) {
+    let radius = (3. * sigma).ceil() as isize;
+    let mut kernel = Vec::with_capacity((2 * radius + 1) as usize);
+    let mut sum = 0.;
+    for x in -radius..=radius {
+        let weight = (-(x * x) as f32 / (2. * sigma * sigma)).exp();
+        kernel.push(weight);
+        sum += weight;
+    }
+    for weight in kernel.iter_mut() {
+        *weight /= sum;
+    }
+
+    let sample = |pixels: &[u32], x: isize, y: isize| -> u32 {
+        let x = x.clamp(0, width as isize - 1) as usize;
+        let y = y.clamp(0, height as isize - 1) as usize;
+        pixels[y * width + x]
+    };
+    let convolve = |pixels: &[u32], cx: isize, cy: isize, horizontal: bool| -> u32 {
+        let (mut a, mut r, mut g, mut b) = (0., 0., 0., 0.);
+        for (i, weight) in kernel.iter().enumerate() {
+            let d = i as isize - radius;
+            let px = if horizontal {
+                sample(pixels, cx + d, cy)
+            } else {
+                sample(pixels, cx, cy + d)
+            };
+            a += weight * ((px >> 24) & 0xff) as f32;
+            r += weight * ((px >> 16) & 0xff) as f32;
+            g += weight * ((px >> 8) & 0xff) as f32;
+            b += weight * (px & 0xff) as f32;
+        }
+        ((a.round() as u32) << 24) |
+            ((r.round() as u32) << 16) |
+            ((g.round() as u32) << 8) |
+            (b.round() as u32)
+    };
+
+    let mut tmp = vec![0u32; pixels.len()];
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            tmp[y as usize * width + x as usize] = convolve(pixels, x, y, true);
+        }
+    }
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            pixels[y as usize * width + x as usize] = convolve(&tmp, x, y, false);
         }
     }
 }
 
+/// Reinterpret a premultiplied BGRA byte buffer as packed `u32` pixels for raqote.
+fn pixels_to_u32(data: &[u8]) -> Vec<u32> {
+    data.chunks_exact(4)
+        .map(|p| u32::from_le_bytes([p[0], p[1], p[2], p[3]]))
+        .collect()
+}
+
+impl ExtendMode {
+    /// Map the canvas extend mode onto the raqote spread used by gradient sources.
+    fn to_raqote_spread(&self) -> raqote::Spread {
+        match self {
+            ExtendMode::Raqote(spread) => *spread,
+        }
+    }
+}
+
+/// Build a CSS-style sweep/conic gradient source spanning `start_angle`..`end_angle`
+/// around `center`. The conic-gradient canvas path can wire this up once it lands.
+pub fn create_sweep_gradient<'a>(
+    gradient: raqote::Gradient,
+    center: raqote::Point,
+    start_angle: f32,
+    end_angle: f32,
+    extend_mode: ExtendMode,
+) -> Option<raqote::Source<'a>> {
+    if gradient.stops.is_empty() {
+        return None;
+    }
+    Some(raqote::Source::new_sweep_gradient(
+        gradient,
+        center,
+        start_angle,
+        end_angle,
+        extend_mode.to_raqote_spread(),
+    ))
+}
+
 impl Color {
     fn as_raqote(&self) -> &raqote::SolidSource {
         match self {
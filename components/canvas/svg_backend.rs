@@ -0,0 +1,340 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! A vector-recording [`GenericDrawTarget`] that, instead of rasterizing, serializes
+//! the drawing commands it receives into an SVG document via the lightweight
+//! `svg_fmt` formatting helpers. This is handy for vector export and for debugging
+//! what the canvas code actually emits. Obtain the document through
+//! [`GenericDrawTarget::snapshot_data_owned`], which returns UTF-8 SVG bytes.
+
+use crate::canvas_data::{
+    Color, CompositionOp, DrawOptions, ExtendMode, Filter, GenericDrawTarget,
+    GenericPathBuilder, GradientStop, GradientStops, Path, Pattern, SourceSurface,
+    StrokeOptions, SurfaceFormat,
+};
+use euclid::default::{Point2D, Rect, Size2D, Transform2D, Vector2D};
+use std::fmt::Write;
+use svg_fmt::{BeginSvg, EndSvg};
+
+/// Format a pattern as an SVG paint string (`rgb(...)` for solids). Gradients are
+/// approximated as black until `<linearGradient>`/`<radialGradient>` defs are wired
+/// through; callers that need them can extend this.
+fn paint(pattern: &Pattern) -> String {
+    match pattern.as_raqote() {
+        raqote::Source::Solid(c) => format!("rgb({},{},{})", c.r, c.g, c.b),
+        _ => "black".to_owned(),
+    }
+}
+
+/// Build the `stroke`, `stroke-width`, `stroke-linejoin`, `stroke-linecap` and
+/// `stroke-dasharray` attributes for a stroked shape.
+fn stroke_attrs(pattern: &Pattern, options: &StrokeOptions) -> String {
+    let style = options.as_raqote();
+    let join = match style.join {
+        raqote::LineJoin::Round => "round",
+        raqote::LineJoin::Bevel => "bevel",
+        raqote::LineJoin::Miter => "miter",
+    };
+    let cap = match style.cap {
+        raqote::LineCap::Butt => "butt",
+        raqote::LineCap::Round => "round",
+        raqote::LineCap::Square => "square",
+    };
+    let mut attrs = format!(
+        "stroke=\"{}\" stroke-width=\"{}\" stroke-linejoin=\"{}\" stroke-linecap=\"{}\"",
+        paint(pattern),
+        style.width,
+        join,
+        cap,
+    );
+    if !style.dash_array.is_empty() {
+        let dashes: Vec<String> = style.dash_array.iter().map(|d| d.to_string()).collect();
+        write!(
+            attrs,
+            " stroke-dasharray=\"{}\" stroke-dashoffset=\"{}\"",
+            dashes.join(","),
+            style.dash_offset,
+        )
+        .unwrap();
+    }
+    attrs
+}
+
+pub struct SvgDrawTarget {
+    size: Size2D<i32>,
+    transform: Transform2D<f32>,
+    /// Body of the `<svg>` element, appended to as draw calls arrive.
+    body: String,
+    /// `<clipPath>` definitions accumulated in `<defs>`, and the open-group depth.
+    defs: String,
+    clip_depth: usize,
+    clip_id: usize,
+}
+
+impl SvgDrawTarget {
+    pub fn new(size: Size2D<i32>) -> SvgDrawTarget {
+        SvgDrawTarget {
+            size,
+            transform: Transform2D::identity(),
+            body: String::new(),
+            defs: String::new(),
+            clip_id: 0,
+            clip_depth: 0,
+        }
+    }
+
+    /// Format a path's segments as SVG path data, pre-multiplying each point by the
+    /// current transform so the output is already in device space.
+    fn path_data(&self, path: &Path) -> String {
+        let mut data = String::new();
+        let p = |data: &mut String, point: raqote::Point| {
+            let point = self
+                .transform
+                .transform_point(Point2D::new(point.x, point.y));
+            write!(data, "{} {} ", point.x, point.y).unwrap();
+        };
+        for op in &path.as_raqote().ops {
+            match op {
+                raqote::PathOp::MoveTo(pt) => {
+                    data.push_str("M ");
+                    p(&mut data, *pt);
+                },
+                raqote::PathOp::LineTo(pt) => {
+                    data.push_str("L ");
+                    p(&mut data, *pt);
+                },
+                raqote::PathOp::QuadTo(c, pt) => {
+                    data.push_str("Q ");
+                    p(&mut data, *c);
+                    p(&mut data, *pt);
+                },
+                raqote::PathOp::CubicTo(c1, c2, pt) => {
+                    data.push_str("C ");
+                    p(&mut data, *c1);
+                    p(&mut data, *c2);
+                    p(&mut data, *pt);
+                },
+                raqote::PathOp::Close => data.push_str("Z "),
+            }
+        }
+        data
+    }
+
+}
+
+impl GenericDrawTarget for SvgDrawTarget {
+    fn clear_rect(&mut self, _rect: &Rect<f32>) {}
+
+    fn copy_surface(
+        &mut self,
+        _surface: SourceSurface,
+        _source: Rect<i32>,
+        _destination: Point2D<i32>,
+    ) {
+    }
+
+    fn create_gradient_stops(
+        &self,
+        gradient_stops: Vec<GradientStop>,
+        _extend_mode: ExtendMode,
+    ) -> GradientStops {
+        let mut stops = gradient_stops;
+        stops.sort_by(|a, b| a.position.partial_cmp(&b.position).unwrap());
+        stops
+    }
+
+    fn create_path_builder(&self) -> Box<dyn GenericPathBuilder> {
+        Box::new(crate::raqote_backend::PathBuilder::new())
+    }
+
+    fn create_similar_draw_target(
+        &self,
+        size: &Size2D<i32>,
+        _format: SurfaceFormat,
+    ) -> Box<dyn GenericDrawTarget> {
+        Box::new(SvgDrawTarget::new(*size))
+    }
+
+    fn create_source_surface_from_data(
+        &self,
+        _data: &[u8],
+        _size: Size2D<i32>,
+        _stride: i32,
+    ) -> Option<SourceSurface> {
+        None
+    }
+
+    fn draw_surface(
+        &mut self,
+        _surface: SourceSurface,
+        _dest: Rect<f64>,
+        _source: Rect<f64>,
+        _filter: Filter,
+        _draw_options: &DrawOptions,
+    ) {
+    }
+
+    fn draw_surface_with_shadow(
+        &mut self,
+        _surface: SourceSurface,
+        _dest: &Point2D<f32>,
+        _color: &Color,
+        _offset: &Vector2D<f32>,
+        _sigma: f32,
+        _operator: CompositionOp,
+    ) {
+    }
+
+    fn fill(&mut self, path: &Path, pattern: Pattern, _draw_options: &DrawOptions) {
+        writeln!(
+            self.body,
+            "  <path d=\"{}\" fill=\"{}\"/>",
+            self.path_data(path),
+            paint(&pattern),
+        )
+        .unwrap();
+    }
+
+    fn fill_rect(&mut self, rect: &Rect<f32>, pattern: Pattern, _draw_options: Option<&DrawOptions>) {
+        let origin = self.transform.transform_point(rect.origin);
+        writeln!(
+            self.body,
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            origin.x,
+            origin.y,
+            rect.size.width,
+            rect.size.height,
+            paint(&pattern),
+        )
+        .unwrap();
+    }
+
+    fn get_format(&self) -> SurfaceFormat {
+        SurfaceFormat::Raqote(())
+    }
+
+    fn get_size(&self) -> Size2D<i32> {
+        self.size
+    }
+
+    fn get_transform(&self) -> Transform2D<f32> {
+        self.transform
+    }
+
+    fn pop_clip(&mut self) {
+        if self.clip_depth > 0 {
+            self.clip_depth -= 1;
+            self.body.push_str("  </g>\n");
+        }
+    }
+
+    fn push_clip(&mut self, path: &Path) {
+        let id = self.clip_id;
+        self.clip_id += 1;
+        writeln!(
+            self.defs,
+            "    <clipPath id=\"clip{}\"><path d=\"{}\"/></clipPath>",
+            id,
+            self.path_data(path),
+        )
+        .unwrap();
+        writeln!(self.body, "  <g clip-path=\"url(#clip{})\">", id).unwrap();
+        self.clip_depth += 1;
+    }
+
+    fn set_transform(&mut self, matrix: &Transform2D<f32>) {
+        self.transform = *matrix;
+    }
+
+    fn snapshot(&self) -> SourceSurface {
+        Vec::new()
+    }
+
+    fn stroke(
+        &mut self,
+        path: &Path,
+        pattern: Pattern,
+        stroke_options: &StrokeOptions,
+        _draw_options: &DrawOptions,
+    ) {
+        writeln!(
+            self.body,
+            "  <path d=\"{}\" fill=\"none\" {}/>",
+            self.path_data(path),
+            stroke_attrs(&pattern, stroke_options),
+        )
+        .unwrap();
+    }
+
+    fn stroke_line(
+        &mut self,
+        start: Point2D<f32>,
+        end: Point2D<f32>,
+        pattern: Pattern,
+        stroke_options: &StrokeOptions,
+        _draw_options: &DrawOptions,
+    ) {
+        let start = self.transform.transform_point(start);
+        let end = self.transform.transform_point(end);
+        writeln!(
+            self.body,
+            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {}/>",
+            start.x,
+            start.y,
+            end.x,
+            end.y,
+            stroke_attrs(&pattern, stroke_options),
+        )
+        .unwrap();
+    }
+
+    fn stroke_rect(
+        &mut self,
+        rect: &Rect<f32>,
+        pattern: Pattern,
+        stroke_options: &StrokeOptions,
+        _draw_options: &DrawOptions,
+    ) {
+        let origin = self.transform.transform_point(rect.origin);
+        writeln!(
+            self.body,
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" {}/>",
+            origin.x,
+            origin.y,
+            rect.size.width,
+            rect.size.height,
+            stroke_attrs(&pattern, stroke_options),
+        )
+        .unwrap();
+    }
+
+    fn snapshot_data(&self, _f: &dyn Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+        self.snapshot_data_owned()
+    }
+
+    fn snapshot_data_owned(&self) -> Vec<u8> {
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "{}",
+            BeginSvg {
+                w: self.size.width as f32,
+                h: self.size.height as f32,
+            }
+        )
+        .unwrap();
+        if !self.defs.is_empty() {
+            svg.push_str("  <defs>\n");
+            svg.push_str(&self.defs);
+            svg.push_str("  </defs>\n");
+        }
+        svg.push_str(&self.body);
+        // Close any clip groups that were never popped.
+        for _ in 0..self.clip_depth {
+            svg.push_str("  </g>\n");
+        }
+        writeln!(svg, "{}", EndSvg).unwrap();
+        svg.into_bytes()
+    }
+}
@@ -0,0 +1,354 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Shared base64 and hexadecimal codecs. This wraps the `base64` crate's `GeneralPurpose`
+//! engines with the exact configurations the platform needs — the HTML
+//! [forgiving-base64] rules behind `atob`/`btoa`, and the [TC39 `Uint8Array`] base64/hex
+//! conversions — so both surfaces share one definition of padding and alphabet handling.
+//!
+//! [forgiving-base64]: https://infra.spec.whatwg.org/#forgiving-base64
+//! [TC39 `Uint8Array`]: https://tc39.es/proposal-arraybuffer-base64/
+
+use base64::alphabet;
+use base64::engine::general_purpose::GeneralPurposeConfig;
+use base64::engine::{DecodePaddingMode, Engine, GeneralPurpose};
+
+/// The alphabet a TC39 base64 conversion uses.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum Base64Alphabet {
+    /// The standard `+/` alphabet (the default).
+    #[default]
+    Standard,
+    /// The URL- and filename-safe `-_` alphabet.
+    Base64Url,
+}
+
+/// How a TC39 decode treats a final chunk that is not a whole 4-character group.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum LastChunkHandling {
+    /// Accept the final chunk with or without padding, and with non-zero
+    /// trailing bits (the default).
+    #[default]
+    Loose,
+    /// Require no padding on the final chunk.
+    StrictNoPadding,
+    /// Leave a trailing partial chunk undecoded instead of erroring on it.
+    Stop,
+}
+
+/// Errors surfaced by the TC39 conversions, mirroring the exceptions the proposal throws.
+#[derive(Debug, PartialEq)]
+pub(crate) enum CodecError {
+    /// Input contained a character outside the selected alphabet.
+    InvalidCharacter,
+    /// Input length or padding was malformed.
+    InvalidLength,
+}
+
+/// The HTML space characters stripped before a forgiving-base64 decode.
+const HTML_SPACE_CHARACTERS: &[char] = &['\u{0020}', '\u{0009}', '\u{000a}', '\u{000c}', '\u{000d}'];
+
+fn standard_alphabet(alphabet: Base64Alphabet) -> &'static alphabet::Alphabet {
+    match alphabet {
+        Base64Alphabet::Standard => &alphabet::STANDARD,
+        Base64Alphabet::Base64Url => &alphabet::URL_SAFE,
+    }
+}
+
+/// Encode `bytes` with the standard alphabet and trailing padding, per HTML `btoa`.
+pub(crate) fn encode_standard(bytes: &[u8]) -> String {
+    let config = GeneralPurposeConfig::new().with_encode_padding(true);
+    GeneralPurpose::new(&alphabet::STANDARD, config).encode(bytes)
+}
+
+/// Decode a string already validated by the HTML forgiving-base64 preprocessing (spaces
+/// removed, padding stripped, alphabet checked). Returns `None` on any residual error.
+pub(crate) fn decode_forgiving(input: &str) -> Option<Vec<u8>> {
+    let config = GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::RequireNone)
+        .with_decode_allow_trailing_bits(true);
+    GeneralPurpose::new(&alphabet::STANDARD, config)
+        .decode(input)
+        .ok()
+}
+
+/// Strip HTML space characters, the preprocessing step shared by `atob` and
+/// `Uint8Array.fromBase64`.
+pub(crate) fn strip_html_spaces(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| !HTML_SPACE_CHARACTERS.contains(c))
+        .collect()
+}
+
+/// <https://tc39.es/proposal-arraybuffer-base64/#sec-uint8array.frombase64> — decode `text`
+/// into bytes with the given alphabet and final-chunk policy.
+pub(crate) fn from_base64(
+    text: &str,
+    alphabet: Base64Alphabet,
+    last_chunk: LastChunkHandling,
+) -> Result<Vec<u8>, CodecError> {
+    let stripped = strip_html_spaces(text);
+    // `Stop` forbids a trailing partial (non-multiple-of-4) group, but rather
+    // than erroring on it, it is simply left undecoded.
+    let input = match last_chunk {
+        LastChunkHandling::Stop => {
+            let char_count = stripped.chars().count();
+            let complete_len = char_count - (char_count % 4);
+            stripped.chars().take(complete_len).collect()
+        },
+        _ => stripped,
+    };
+    let padding = match last_chunk {
+        LastChunkHandling::StrictNoPadding => DecodePaddingMode::RequireNone,
+        LastChunkHandling::Loose | LastChunkHandling::Stop => DecodePaddingMode::Indifferent,
+    };
+    let config = GeneralPurposeConfig::new()
+        .with_decode_padding_mode(padding)
+        .with_decode_allow_trailing_bits(matches!(last_chunk, LastChunkHandling::Loose));
+    GeneralPurpose::new(standard_alphabet(alphabet), config)
+        .decode(input)
+        .map_err(|error| match error {
+            base64::DecodeError::InvalidLength(_) | base64::DecodeError::InvalidPadding => {
+                CodecError::InvalidLength
+            },
+            _ => CodecError::InvalidCharacter,
+        })
+}
+
+/// The `{ read, written }` result of decoding directly into an existing buffer:
+/// <https://tc39.es/proposal-arraybuffer-base64/#sec-uint8array.prototype.setfrombase64>.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct DecodeIntoResult {
+    /// Number of characters consumed from the original (unstripped) input.
+    pub(crate) read: usize,
+    /// Number of bytes written into the destination.
+    pub(crate) written: usize,
+}
+
+/// <https://tc39.es/proposal-arraybuffer-base64/#sec-uint8array.prototype.setfrombase64> —
+/// decode `text` directly into `destination`, one 4-character group at a time,
+/// stopping once `destination` is full rather than erroring. A trailing
+/// partial group is only consulted against `last_chunk` once actually
+/// reached; filling the destination first leaves it, along with the rest of
+/// `text`, unread so a caller can resume decoding from `read` onward.
+pub(crate) fn set_from_base64(
+    destination: &mut [u8],
+    text: &str,
+    alphabet: Base64Alphabet,
+    last_chunk: LastChunkHandling,
+) -> Result<DecodeIntoResult, CodecError> {
+    let stripped = strip_html_spaces(text);
+    let chars: Vec<char> = stripped.chars().collect();
+    let mut read_chars = 0;
+    let mut written = 0;
+
+    for group in chars.chunks(4) {
+        let is_last_group = read_chars + group.len() == chars.len();
+        let group_text: String = group.iter().collect();
+        let decoded = if group.len() < 4 && is_last_group {
+            if matches!(last_chunk, LastChunkHandling::Stop) {
+                break;
+            }
+            from_base64(&group_text, alphabet, last_chunk)?
+        } else {
+            from_base64(&group_text, alphabet, LastChunkHandling::Loose)?
+        };
+
+        if written + decoded.len() > destination.len() {
+            break;
+        }
+        destination[written..written + decoded.len()].copy_from_slice(&decoded);
+        written += decoded.len();
+        read_chars += group.len();
+    }
+
+    Ok(DecodeIntoResult {
+        read: stripped_prefix_byte_len(text, read_chars),
+        written,
+    })
+}
+
+/// The byte length of the shortest prefix of `text` whose HTML-space-stripped
+/// form is `stripped_chars` characters long.
+fn stripped_prefix_byte_len(text: &str, stripped_chars: usize) -> usize {
+    let mut seen = 0;
+    for (offset, c) in text.char_indices() {
+        if seen == stripped_chars {
+            return offset;
+        }
+        if !HTML_SPACE_CHARACTERS.contains(&c) {
+            seen += 1;
+        }
+    }
+    text.len()
+}
+
+/// <https://tc39.es/proposal-arraybuffer-base64/#sec-uint8array.prototype.tobase64> — encode
+/// `bytes`, optionally omitting the trailing padding.
+pub(crate) fn to_base64(bytes: &[u8], alphabet: Base64Alphabet, omit_padding: bool) -> String {
+    let config = GeneralPurposeConfig::new().with_encode_padding(!omit_padding);
+    GeneralPurpose::new(standard_alphabet(alphabet), config).encode(bytes)
+}
+
+/// <https://tc39.es/proposal-arraybuffer-base64/#sec-uint8array.fromhex> — decode a hex string
+/// into bytes. The input must have even length and contain only ASCII hex digits.
+pub(crate) fn from_hex(text: &str) -> Result<Vec<u8>, CodecError> {
+    if text.len() % 2 != 0 {
+        return Err(CodecError::InvalidLength);
+    }
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len() / 2);
+    for pair in bytes.chunks_exact(2) {
+        let hi = hex_value(pair[0]).ok_or(CodecError::InvalidCharacter)?;
+        let lo = hex_value(pair[1]).ok_or(CodecError::InvalidCharacter)?;
+        out.push(hi << 4 | lo);
+    }
+    Ok(out)
+}
+
+/// <https://tc39.es/proposal-arraybuffer-base64/#sec-uint8array.prototype.setfromhex> —
+/// decode `text` directly into `destination`, one byte (two hex digits) at a
+/// time, stopping once `destination` is full rather than erroring.
+pub(crate) fn set_from_hex(destination: &mut [u8], text: &str) -> Result<DecodeIntoResult, CodecError> {
+    let bytes = text.as_bytes();
+    let pairs = bytes.len() / 2;
+    let written = pairs.min(destination.len());
+    for i in 0..written {
+        let hi = hex_value(bytes[2 * i]).ok_or(CodecError::InvalidCharacter)?;
+        let lo = hex_value(bytes[2 * i + 1]).ok_or(CodecError::InvalidCharacter)?;
+        destination[i] = hi << 4 | lo;
+    }
+    Ok(DecodeIntoResult {
+        read: written * 2,
+        written,
+    })
+}
+
+/// <https://tc39.es/proposal-arraybuffer-base64/#sec-uint8array.prototype.tohex> — encode
+/// `bytes` as a lowercase hex string.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push(hex_digit(byte >> 4));
+        out.push(hex_digit(byte & 0x0f));
+    }
+    out
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn hex_digit(nibble: u8) -> char {
+    char::from_digit(nibble as u32, 16).expect("nibble is always < 16")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_standard_pads() {
+        assert_eq!(encode_standard(b"any carnal pleasure"), "YW55IGNhcm5hbCBwbGVhc3VyZQ==");
+    }
+
+    #[test]
+    fn decode_forgiving_roundtrips() {
+        let bytes = b"hello world";
+        let encoded = encode_standard(bytes);
+        assert_eq!(decode_forgiving(&encoded).as_deref(), Some(bytes.as_slice()));
+    }
+
+    #[test]
+    fn decode_forgiving_rejects_invalid_padding() {
+        assert_eq!(decode_forgiving("a==="), None);
+    }
+
+    #[test]
+    fn strip_html_spaces_removes_all_five_characters() {
+        assert_eq!(strip_html_spaces("a \t\n\x0c\rb"), "ab");
+    }
+
+    #[test]
+    fn from_base64_roundtrips_with_url_safe_alphabet() {
+        let bytes = &[0xfb, 0xff, 0xbf][..];
+        let encoded = to_base64(bytes, Base64Alphabet::Base64Url, false);
+        assert_eq!(
+            from_base64(&encoded, Base64Alphabet::Base64Url, LastChunkHandling::Loose),
+            Ok(bytes.to_vec())
+        );
+    }
+
+    #[test]
+    fn from_base64_strict_rejects_padding() {
+        assert_eq!(
+            from_base64("YQ==", Base64Alphabet::Standard, LastChunkHandling::StrictNoPadding),
+            Err(CodecError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn to_base64_can_omit_padding() {
+        assert_eq!(to_base64(b"a", Base64Alphabet::Standard, true), "YQ");
+        assert_eq!(to_base64(b"a", Base64Alphabet::Standard, false), "YQ==");
+    }
+
+    #[test]
+    fn hex_roundtrips() {
+        let bytes = &[0x00, 0x0f, 0xff, 0xa5][..];
+        let hex = to_hex(bytes);
+        assert_eq!(hex, "000fffa5");
+        assert_eq!(from_hex(&hex), Ok(bytes.to_vec()));
+    }
+
+    #[test]
+    fn from_hex_rejects_odd_length_and_bad_digits() {
+        assert_eq!(from_hex("abc"), Err(CodecError::InvalidLength));
+        assert_eq!(from_hex("zz"), Err(CodecError::InvalidCharacter));
+    }
+
+    #[test]
+    fn from_base64_stop_drops_trailing_partial_chunk() {
+        // "YQ" (2 chars) is not a whole 4-character group and is left unread,
+        // rather than erroring as it would under the other handlings.
+        assert_eq!(
+            from_base64("YWJjYQ", Base64Alphabet::Standard, LastChunkHandling::Stop),
+            Ok(b"abc".to_vec())
+        );
+        assert_eq!(
+            from_base64("YWJj", Base64Alphabet::Standard, LastChunkHandling::Loose),
+            from_base64("YWJjYQ", Base64Alphabet::Standard, LastChunkHandling::Stop),
+        );
+    }
+
+    #[test]
+    fn set_from_base64_fills_destination_and_reports_read_written() {
+        let mut destination = [0u8; 3];
+        let result = set_from_base64(
+            &mut destination,
+            "YWJjZA==",
+            Base64Alphabet::Standard,
+            LastChunkHandling::Loose,
+        )
+        .unwrap();
+        // Only the first group ("YWJj" -> "abc") fits fully; the second group
+        // ("ZA==" -> "d") is left unread since it would overflow the buffer.
+        assert_eq!(result, DecodeIntoResult { read: 4, written: 3 });
+        assert_eq!(&destination, b"abc");
+    }
+
+    #[test]
+    fn set_from_hex_fills_destination_and_reports_read_written() {
+        let mut destination = [0u8; 2];
+        let result = set_from_hex(&mut destination, "000fffa5").unwrap();
+        assert_eq!(result, DecodeIntoResult { read: 4, written: 2 });
+        assert_eq!(&destination, &[0x00, 0x0f]);
+    }
+}
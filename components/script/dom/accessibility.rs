@@ -0,0 +1,228 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! A lazily-built accessibility (AX) tree rooted at a [`Window`], plus the lifecycle
+//! events surfaced to the embedder / assistive technology. The tree is computed from
+//! the live DOM — element semantics and ARIA attributes — and cached between queries;
+//! it is rebuilt only after an explicit [`AccessibilityTree::invalidate`], which no DOM
+//! mutation path calls.
+
+use std::cell::RefCell;
+
+use crate::dom::bindings::inheritance::Castable;
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::document::Document;
+use crate::dom::element::Element;
+use crate::dom::node::Node;
+use crate::dom::window::Window;
+
+/// A node in the serialized accessibility tree.
+#[derive(Clone, Debug)]
+pub(crate) struct AccessibilityNode {
+    /// Identifier taken from the originating DOM node's opaque address.
+    pub(crate) id: u64,
+    /// Computed ARIA role (e.g. `"button"`, `"heading"`, `"generic"`).
+    pub(crate) role: String,
+    /// Accessible name, from `aria-label` or text content.
+    pub(crate) name: String,
+    /// Accessible value slot for form controls; `build_node` never fills it, so it is
+    /// always `None`.
+    pub(crate) value: Option<String>,
+    /// CSS-pixel content-box bounds as `(x, y, width, height)`.
+    pub(crate) bounds: (f32, f32, f32, f32),
+    /// Packed state flags (focused, hidden, disabled, …).
+    pub(crate) states: AccessibilityStates,
+    pub(crate) children: Vec<AccessibilityNode>,
+}
+
+/// State flags for an accessibility node.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct AccessibilityStates {
+    pub(crate) hidden: bool,
+    pub(crate) focused: bool,
+    pub(crate) disabled: bool,
+}
+
+/// A lifecycle event emitted to the embedder over the accessibility channel.
+#[derive(Clone, Debug)]
+pub(crate) enum AccessibilityEvent {
+    /// Fired once the document's `load` event has fired.
+    DocumentLoadComplete,
+    /// Fired whenever an `alert()` dialog surfaces, carrying its message.
+    Alert(String),
+}
+
+/// Caches the last-built AX tree and tracks whether the DOM changed since.
+#[derive(Default)]
+pub(crate) struct AccessibilityTree {
+    tree: RefCell<Option<AccessibilityNode>>,
+    dirty: RefCell<bool>,
+}
+
+impl AccessibilityTree {
+    /// Mark the cached tree stale. `Window::invalidate_accessibility_tree` forwards here;
+    /// no DOM mutation path calls either.
+    pub(crate) fn invalidate(&self) {
+        *self.dirty.borrow_mut() = true;
+    }
+
+    /// Return the root node, rebuilding from `window`'s document only when the cache is stale.
+    pub(crate) fn root(&self, window: &Window) -> AccessibilityNode {
+        if self.tree.borrow().is_none() || *self.dirty.borrow() {
+            let document = window.Document();
+            let root = document
+                .GetDocumentElement()
+                .map(|element| build_node(window, &document, element.upcast::<Node>()))
+                .unwrap_or_else(AccessibilityNode::empty);
+            *self.tree.borrow_mut() = Some(root);
+            *self.dirty.borrow_mut() = false;
+        }
+        self.tree.borrow().clone().unwrap()
+    }
+
+    /// Return the subtree rooted at `node_id`, if present.
+    pub(crate) fn subtree(&self, window: &Window, node_id: u64) -> Option<AccessibilityNode> {
+        find_subtree(&self.root(window), node_id)
+    }
+}
+
+impl AccessibilityNode {
+    fn empty() -> AccessibilityNode {
+        AccessibilityNode {
+            id: 0,
+            role: "generic".to_owned(),
+            name: String::new(),
+            value: None,
+            bounds: (0., 0., 0., 0.),
+            states: AccessibilityStates::default(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Compute the AX node for `node` and recurse into its element children; shadow trees
+/// are not traversed.
+fn build_node(window: &Window, document: &Document, node: &Node) -> AccessibilityNode {
+    let element = node.downcast::<Element>();
+    let role = element
+        .map(compute_role)
+        .unwrap_or_else(|| "generic".to_owned());
+    let name = element.map(compute_name).unwrap_or_default();
+    let states = element
+        .map(|element| compute_states(element, document))
+        .unwrap_or_default();
+    let bounds = compute_bounds(window, node);
+
+    let children = node
+        .children()
+        .filter(|child| child.is::<Element>())
+        .map(|child| build_node(window, document, &child))
+        .collect();
+
+    AccessibilityNode {
+        id: node.to_opaque().0 as u64,
+        role,
+        name,
+        value: None,
+        bounds,
+        states,
+        children,
+    }
+}
+
+/// CSS-pixel content-box bounds for `node`, as last measured by layout.
+/// `(0., 0., 0., 0.)` for a node layout hasn't measured yet, e.g. `display: none`.
+fn compute_bounds(window: &Window, node: &Node) -> (f32, f32, f32, f32) {
+    window
+        .content_box_query_unchecked(node)
+        .map(|rect| {
+            (
+                rect.origin.x.to_f32_px(),
+                rect.origin.y.to_f32_px(),
+                rect.size.width.to_f32_px(),
+                rect.size.height.to_f32_px(),
+            )
+        })
+        .unwrap_or((0., 0., 0., 0.))
+}
+
+/// Map an element to its ARIA role, honoring an explicit `role` attribute.
+fn compute_role(element: &Element) -> String {
+    if let Some(role) = element.get_string_attribute(&local_name!("role")) {
+        if !role.is_empty() {
+            return role.to_string();
+        }
+    }
+    match element.local_name() {
+        name if name == &local_name!("a") => "link",
+        name if name == &local_name!("button") => "button",
+        name if name == &local_name!("h1") ||
+            name == &local_name!("h2") ||
+            name == &local_name!("h3") ||
+            name == &local_name!("h4") ||
+            name == &local_name!("h5") ||
+            name == &local_name!("h6") =>
+        {
+            "heading"
+        },
+        name if name == &local_name!("img") => "image",
+        name if name == &local_name!("input") => compute_input_role(element),
+        name if name == &local_name!("nav") => "navigation",
+        _ => "generic",
+    }
+    .to_owned()
+}
+
+/// Map an `<input>` to its ARIA role based on its `type` attribute, defaulting
+/// to `"textbox"` for the text-like and unrecognized types.
+fn compute_input_role(element: &Element) -> &'static str {
+    match element
+        .get_string_attribute(&local_name!("type"))
+        .map(|value| value.to_string().to_lowercase())
+        .as_deref()
+    {
+        Some("checkbox") => "checkbox",
+        Some("radio") => "radio",
+        Some("button" | "submit" | "reset") => "button",
+        Some("range") => "slider",
+        Some("number") => "spinbutton",
+        Some("search") => "searchbox",
+        _ => "textbox",
+    }
+}
+
+/// Compute an accessible name, preferring `aria-label` then text content.
+fn compute_name(element: &Element) -> String {
+    if let Some(label) = element.get_string_attribute(&local_name!("aria-label")) {
+        if !label.is_empty() {
+            return label.to_string();
+        }
+    }
+    element
+        .upcast::<Node>()
+        .GetTextContent()
+        .map(|text| text.to_string())
+        .unwrap_or_default()
+}
+
+fn compute_states(element: &Element, document: &Document) -> AccessibilityStates {
+    AccessibilityStates {
+        hidden: element
+            .get_string_attribute(&local_name!("aria-hidden"))
+            .is_some_and(|value| value == "true"),
+        focused: document
+            .get_focused_element()
+            .is_some_and(|focused| std::ptr::eq(&*focused, element)),
+        disabled: element.has_attribute(&local_name!("disabled")),
+    }
+}
+
+fn find_subtree(node: &AccessibilityNode, node_id: u64) -> Option<AccessibilityNode> {
+    if node.id == node_id {
+        return Some(node.clone());
+    }
+    node.children
+        .iter()
+        .find_map(|child| find_subtree(child, node_id))
+}
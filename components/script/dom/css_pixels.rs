@@ -0,0 +1,130 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! A fixed-point representation of a CSS pixel length, used by `Window`'s viewport and
+//! scroll geometry accessors.
+//!
+//! Rounding an `f32`/`f64` CSS pixel value straight to `i32` (as `to_i32().unwrap_or(0)`)
+//! loses sub-pixel precision and, for a `0 < scale < 1` device pixel ratio, can underflow a
+//! small-but-nonzero value to `0`. [`CSSPixels`] keeps a 1/64 px fixed-point representation
+//! through intermediate arithmetic so only the final, explicit conversion to `i32` rounds to
+//! a whole pixel.
+
+/// One CSS pixel is represented as this many fixed-point units.
+const SUBPIXEL_UNITS_PER_PX: i32 = 64;
+
+/// A CSS pixel length stored as a fixed-point integer with 1/64 px resolution.
+///
+/// <https://drafts.csswg.org/cssom-view/>
+#[derive(Clone, Copy, Debug, Default, Eq, JSTraceable, MallocSizeOf, PartialEq)]
+pub(crate) struct CSSPixels(i32);
+
+impl CSSPixels {
+    pub(crate) const fn zero() -> Self {
+        CSSPixels(0)
+    }
+
+    /// Converts a (necessarily finite) CSS pixel value, such as a viewport or scroll offset
+    /// component already known not to be NaN/infinite, into fixed-point units.
+    pub(crate) fn from_px(px: f32) -> Self {
+        CSSPixels((px as f64 * SUBPIXEL_UNITS_PER_PX as f64).round() as i32)
+    }
+
+    pub(crate) fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(CSSPixels)
+    }
+
+    pub(crate) fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(CSSPixels)
+    }
+
+    /// Multiplies by a scalar (e.g. a device pixel ratio), rounding to the nearest
+    /// representable 1/64 px unit rather than deferring all rounding to the final `i32`
+    /// conversion.
+    pub(crate) fn scale_by(self, factor: f64) -> Self {
+        CSSPixels((self.0 as f64 * factor).round() as i32)
+    }
+
+    pub(crate) fn round(self) -> i32 {
+        (self.0 as f64 / SUBPIXEL_UNITS_PER_PX as f64).round() as i32
+    }
+
+    pub(crate) fn floor(self) -> i32 {
+        self.0.div_euclid(SUBPIXEL_UNITS_PER_PX)
+    }
+
+    pub(crate) fn ceil(self) -> i32 {
+        -(-self.0).div_euclid(SUBPIXEL_UNITS_PER_PX)
+    }
+}
+
+impl From<CSSPixels> for f64 {
+    fn from(value: CSSPixels) -> Self {
+        value.0 as f64 / SUBPIXEL_UNITS_PER_PX as f64
+    }
+}
+
+impl TryFrom<f64> for CSSPixels {
+    type Error = ();
+
+    /// Rejects non-finite input so it can't silently become `0` rather than erroring.
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            return Err(());
+        }
+        Ok(CSSPixels((value * SUBPIXEL_UNITS_PER_PX as f64).round() as i32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_px_round_trips_through_round() {
+        assert_eq!(CSSPixels::from_px(10.0).round(), 10);
+        assert_eq!(CSSPixels::from_px(0.5).round(), 1);
+        assert_eq!(CSSPixels::from_px(0.01).round(), 0);
+    }
+
+    #[test]
+    fn floor_and_ceil_handle_negative_values() {
+        let pixels = CSSPixels::from_px(-1.5);
+        assert_eq!(pixels.floor(), -2);
+        assert_eq!(pixels.ceil(), -1);
+    }
+
+    #[test]
+    fn checked_add_and_sub() {
+        let a = CSSPixels::from_px(1.0);
+        let b = CSSPixels::from_px(2.0);
+        assert_eq!(a.checked_add(b).unwrap().round(), 3);
+        assert_eq!(b.checked_sub(a).unwrap().round(), 1);
+    }
+
+    #[test]
+    fn checked_add_overflows_to_none() {
+        let max = CSSPixels::try_from(f64::from(i32::MAX)).unwrap();
+        assert!(max.checked_add(CSSPixels::from_px(1.0)).is_none());
+    }
+
+    #[test]
+    fn scale_by_rounds_to_nearest_subpixel() {
+        let scaled = CSSPixels::from_px(10.0).scale_by(1.333);
+        assert_eq!(scaled.round(), 13);
+    }
+
+    #[test]
+    fn try_from_rejects_non_finite() {
+        assert!(CSSPixels::try_from(f64::NAN).is_err());
+        assert!(CSSPixels::try_from(f64::INFINITY).is_err());
+        assert!(CSSPixels::try_from(2.0).is_ok());
+    }
+
+    #[test]
+    fn from_css_pixels_round_trips_to_f64() {
+        let pixels = CSSPixels::try_from(1.328125).unwrap();
+        assert_eq!(f64::from(pixels), 1.328125);
+    }
+}
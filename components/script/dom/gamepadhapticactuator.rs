@@ -0,0 +1,168 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use dom_struct::dom_struct;
+use embedder_traits::{GamepadHapticEffectType, GamepadSupportedHapticEffects};
+use ipc_channel::ipc::IpcSender;
+use serde::{Deserialize, Serialize};
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::GamepadHapticActuatorBinding::{
+    self, GamepadEffectParameters, GamepadHapticActuatorMethods, GamepadHapticEffectResult,
+    GamepadHapticEffectType as BindingEffectType,
+};
+use crate::dom::bindings::reflector::{reflect_dom_object, Reflector};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::realms::InRealm;
+use crate::script_runtime::CanGc;
+
+/// A command sent to the input thread to drive force feedback on a device.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GamepadHapticCommand {
+    pub gamepad_index: usize,
+    pub effect_type: GamepadHapticEffectType,
+    pub duration_ms: f64,
+    pub start_delay_ms: f64,
+    pub strong_magnitude: f64,
+    pub weak_magnitude: f64,
+}
+
+/// <https://w3c.github.io/gamepad/extensions.html#gamepadhapticactuator-interface>
+#[dom_struct]
+pub(crate) struct GamepadHapticActuator {
+    reflector_: Reflector,
+    /// The owning gamepad's index, used to target the device.
+    gamepad_index: usize,
+    /// The haptic effect types this device advertises support for.
+    #[ignore_malloc_size_of = "Defined in embedder_traits"]
+    #[no_trace]
+    supported_haptic_effects: GamepadSupportedHapticEffects,
+    /// The in-flight `playEffect` promise, resolved on completion or preemption.
+    #[ignore_malloc_size_of = "Rc"]
+    playing_effect_promise: DomRefCell<Option<Rc<Promise>>>,
+    /// Sequence number distinguishing effects so a stale completion is ignored.
+    sequence_id: Cell<u32>,
+    #[ignore_malloc_size_of = "IpcSender"]
+    #[no_trace]
+    haptic_sender: IpcSender<GamepadHapticCommand>,
+}
+
+impl GamepadHapticActuator {
+    fn new_inherited(
+        gamepad_index: usize,
+        supported_haptic_effects: GamepadSupportedHapticEffects,
+        haptic_sender: IpcSender<GamepadHapticCommand>,
+    ) -> GamepadHapticActuator {
+        GamepadHapticActuator {
+            reflector_: Reflector::new(),
+            gamepad_index,
+            supported_haptic_effects,
+            playing_effect_promise: DomRefCell::new(None),
+            sequence_id: Cell::new(0),
+            haptic_sender,
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        gamepad_index: usize,
+        supported_haptic_effects: GamepadSupportedHapticEffects,
+        haptic_sender: IpcSender<GamepadHapticCommand>,
+    ) -> DomRoot<GamepadHapticActuator> {
+        reflect_dom_object(
+            Box::new(GamepadHapticActuator::new_inherited(
+                gamepad_index,
+                supported_haptic_effects,
+                haptic_sender,
+            )),
+            global,
+            GamepadHapticActuatorBinding::Wrap,
+        )
+    }
+
+    /// Whether this device supports the requested effect type.
+    fn supports(&self, effect_type: GamepadHapticEffectType) -> bool {
+        match effect_type {
+            GamepadHapticEffectType::DualRumble => {
+                self.supported_haptic_effects.supports_dual_rumble
+            },
+            GamepadHapticEffectType::TriggerRumble => {
+                self.supported_haptic_effects.supports_trigger_rumble
+            },
+        }
+    }
+
+    /// Resolve the in-flight promise with `result`, if any, and clear it.
+    fn resolve_playing(&self, result: GamepadHapticEffectResult, can_gc: CanGc) {
+        if let Some(promise) = self.playing_effect_promise.borrow_mut().take() {
+            promise.resolve_native(&result, can_gc);
+        }
+    }
+
+    /// Resolves the in-flight promise with `Complete` when `sequence_id` is that of the
+    /// most recent effect. Nothing in this crate calls it, and `GamepadHapticCommand`
+    /// does not carry the sequence id.
+    pub(crate) fn notify_effect_complete(&self, sequence_id: u32, can_gc: CanGc) {
+        if self.sequence_id.get() == sequence_id {
+            self.resolve_playing(GamepadHapticEffectResult::Complete, can_gc);
+        }
+    }
+}
+
+impl GamepadHapticActuatorMethods for GamepadHapticActuator {
+    /// <https://w3c.github.io/gamepad/extensions.html#dom-gamepadhapticactuator-playeffect>
+    fn PlayEffect(
+        &self,
+        effect_type: BindingEffectType,
+        params: &GamepadEffectParameters,
+        comp: InRealm,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        let promise = Promise::new_in_current_realm(comp, can_gc);
+
+        let effect_type = match effect_type {
+            BindingEffectType::Dual_rumble => GamepadHapticEffectType::DualRumble,
+            BindingEffectType::Trigger_rumble => GamepadHapticEffectType::TriggerRumble,
+        };
+        if !self.supports(effect_type) {
+            promise.resolve_native(&GamepadHapticEffectResult::Complete, can_gc);
+            return promise;
+        }
+
+        // A new effect preempts any in-flight one.
+        self.resolve_playing(GamepadHapticEffectResult::Preempted, can_gc);
+
+        let sequence_id = self.sequence_id.get().wrapping_add(1);
+        self.sequence_id.set(sequence_id);
+        *self.playing_effect_promise.borrow_mut() = Some(promise.clone());
+
+        // Clamp all magnitudes to [0, 1] and durations to non-negative.
+        let clamp01 = |value: f64| value.clamp(0., 1.);
+        let command = GamepadHapticCommand {
+            gamepad_index: self.gamepad_index,
+            effect_type,
+            duration_ms: params.duration.max(0.) as f64,
+            start_delay_ms: params.startDelay.max(0.) as f64,
+            strong_magnitude: clamp01(params.strongMagnitude),
+            weak_magnitude: clamp01(params.weakMagnitude),
+        };
+        let _ = self.haptic_sender.send(command);
+
+        promise
+    }
+
+    /// <https://w3c.github.io/gamepad/extensions.html#dom-gamepadhapticactuator-reset>
+    fn Reset(&self, comp: InRealm, can_gc: CanGc) -> Rc<Promise> {
+        // Resetting preempts the playing effect, if any.
+        self.resolve_playing(GamepadHapticEffectResult::Preempted, can_gc);
+        let promise = Promise::new_in_current_realm(comp, can_gc);
+        promise.resolve_native(&GamepadHapticEffectResult::Complete, can_gc);
+        promise
+    }
+}
@@ -0,0 +1,89 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! A map from a physical gamepad (identified by its GilRs device index) to a gamepad slot
+//! in a `Window`'s [`Navigator`]. Connect/disconnect/update events look the device up here,
+//! so a repeated connect of a known device keeps its slot.
+//!
+//! Each `Window` creates its own registry; it is not shared across windows, so two
+//! documents allocate slots independently of each other.
+//!
+//! [`Navigator`]: crate::dom::navigator::Navigator
+
+use std::collections::HashMap;
+
+use base::id::PipelineId;
+
+/// The canonical, spec-visible index a physical device occupies in `getGamepads()`.
+pub(crate) type GamepadSlot = usize;
+
+/// A single physical device's registry entry.
+#[derive(Debug)]
+struct RegisteredDevice {
+    /// The slot this device occupies in `getGamepads()`.
+    slot: GamepadSlot,
+    /// Pipelines whose documents have exposed this device after a user gesture. Used to
+    /// emit `gamepadconnected` exactly once per document.
+    exposed_to: Vec<PipelineId>,
+}
+
+/// Maps GilRs device indices to gamepad slots for one `Window`.
+#[derive(Default)]
+pub(crate) struct GamepadRegistry {
+    /// Keyed by the GilRs device index reported by the input thread.
+    devices: HashMap<usize, RegisteredDevice>,
+    /// Slots freed by disconnects, reused before growing the index space.
+    free_slots: Vec<GamepadSlot>,
+    /// The next never-used slot, handed out when `free_slots` is empty.
+    next_slot: GamepadSlot,
+}
+
+impl GamepadRegistry {
+    /// Register `device_index` on connect, returning its canonical slot. Re-connecting an
+    /// already-known device returns its existing slot rather than allocating a new one.
+    pub(crate) fn connect(&mut self, device_index: usize) -> GamepadSlot {
+        if let Some(device) = self.devices.get(&device_index) {
+            return device.slot;
+        }
+        let slot = self.free_slots.pop().unwrap_or_else(|| {
+            let slot = self.next_slot;
+            self.next_slot += 1;
+            slot
+        });
+        self.devices.insert(
+            device_index,
+            RegisteredDevice {
+                slot,
+                exposed_to: Vec::new(),
+            },
+        );
+        slot
+    }
+
+    /// Forget `device_index` on disconnect, freeing its slot for reuse. Returns the slot it
+    /// occupied so callers can tear down the per-document `Gamepad` objects.
+    pub(crate) fn disconnect(&mut self, device_index: usize) -> Option<GamepadSlot> {
+        let device = self.devices.remove(&device_index)?;
+        self.free_slots.push(device.slot);
+        Some(device.slot)
+    }
+
+    /// The canonical slot a known device occupies, if any.
+    pub(crate) fn slot(&self, device_index: usize) -> Option<GamepadSlot> {
+        self.devices.get(&device_index).map(|device| device.slot)
+    }
+
+    /// Record that `pipeline` has exposed `device_index`, returning `true` the first time so
+    /// the caller emits `gamepadconnected` only once per document.
+    pub(crate) fn expose(&mut self, device_index: usize, pipeline: PipelineId) -> bool {
+        let Some(device) = self.devices.get_mut(&device_index) else {
+            return false;
+        };
+        if device.exposed_to.contains(&pipeline) {
+            return false;
+        }
+        device.exposed_to.push(pipeline);
+        true
+    }
+}
@@ -0,0 +1,77 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+use std::time::Instant;
+
+use dom_struct::dom_struct;
+
+use crate::dom::bindings::codegen::Bindings::IdleDeadlineBinding::{
+    self, IdleDeadlineMethods,
+};
+use crate::dom::bindings::num::Finite;
+use crate::dom::bindings::reflector::{reflect_dom_object, Reflector};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::globalscope::GlobalScope;
+
+/// The ceiling on `timeRemaining()`, per the spec: an idle period never advertises
+/// more than 50 ms of available time.
+const MAX_TIME_REMAINING_MS: f64 = 50.;
+
+/// The deadline handed to a `requestIdleCallback` callback.
+/// <https://w3c.github.io/requestidlecallback/#the-idledeadline-interface>
+#[dom_struct]
+pub(crate) struct IdleDeadline {
+    reflector_: Reflector,
+    /// The instant by which the idle period is expected to end.
+    #[ignore_malloc_size_of = "Instant"]
+    #[no_trace]
+    deadline: Instant,
+    /// Whether the callback is being run because its registration timed out.
+    did_timeout: bool,
+}
+
+impl IdleDeadline {
+    fn new_inherited(deadline: Instant, did_timeout: bool) -> IdleDeadline {
+        IdleDeadline {
+            reflector_: Reflector::new(),
+            deadline,
+            did_timeout,
+        }
+    }
+
+    pub(crate) fn new(
+        global: &GlobalScope,
+        deadline: Instant,
+        did_timeout: bool,
+    ) -> DomRoot<IdleDeadline> {
+        reflect_dom_object(
+            Box::new(IdleDeadline::new_inherited(deadline, did_timeout)),
+            global,
+            IdleDeadlineBinding::Wrap,
+        )
+    }
+}
+
+impl IdleDeadlineMethods for IdleDeadline {
+    /// <https://w3c.github.io/requestidlecallback/#dom-idledeadline-timeremaining>
+    fn TimeRemaining(&self) -> Finite<f64> {
+        // Timed-out callbacks are run with no idle time available.
+        let remaining = if self.did_timeout {
+            0.
+        } else {
+            let now = Instant::now();
+            if now >= self.deadline {
+                0.
+            } else {
+                (self.deadline - now).as_secs_f64() * 1000.
+            }
+        };
+        Finite::wrap(remaining.min(MAX_TIME_REMAINING_MS).max(0.))
+    }
+
+    /// <https://w3c.github.io/requestidlecallback/#dom-idledeadline-didtimeout>
+    fn DidTimeout(&self) -> bool {
+        self.did_timeout
+    }
+}
@@ -0,0 +1,433 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! <https://html.spec.whatwg.org/multipage/#imagebitmap>
+//!
+//! The cropping/scaling algorithm composes the `sx, sy, sw, sh` crop rect with
+//! `ImageBitmapOptions` (`resizeWidth`/`resizeHeight`/`resizeQuality`, `imageOrientation`,
+//! `premultiplyAlpha`, `colorSpaceConversion`) on a decoded RGBA8 buffer, so every
+//! `ImageBitmapSource` gets the same option handling regardless of how it was decoded.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use dom_struct::dom_struct;
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::ImageBitmapBinding::{
+    self, ImageBitmapMethods, ImageBitmapOptions, ImageBitmapSource, ImageOrientation,
+    PremultiplyAlpha, ResizeQuality,
+};
+use crate::dom::bindings::error::Error;
+use crate::dom::bindings::reflector::{Reflector, reflect_dom_object};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::globalscope::GlobalScope;
+use crate::dom::promise::Promise;
+use crate::script_runtime::CanGc;
+
+/// Non-premultiplied, sRGB, row-major RGBA8 pixels: the common currency every
+/// `ImageBitmapSource` is decoded into before the options below are applied.
+struct DecodedImage {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+/// <https://html.spec.whatwg.org/multipage/#imagebitmap>
+#[dom_struct]
+pub(crate) struct ImageBitmap {
+    reflector_: Reflector,
+    width: Cell<u32>,
+    height: Cell<u32>,
+    /// `None` once [`ImageBitmap::Close`] has detached the bitmap's pixel data.
+    bitmap_data: DomRefCell<Option<Vec<u8>>>,
+}
+
+impl ImageBitmap {
+    fn new_inherited(width: u32, height: u32, data: Vec<u8>) -> ImageBitmap {
+        ImageBitmap {
+            reflector_: Reflector::new(),
+            width: Cell::new(width),
+            height: Cell::new(height),
+            bitmap_data: DomRefCell::new(Some(data)),
+        }
+    }
+
+    fn new(global: &GlobalScope, width: u32, height: u32, data: Vec<u8>) -> DomRoot<ImageBitmap> {
+        reflect_dom_object(
+            Box::new(ImageBitmap::new_inherited(width, height, data)),
+            global,
+            ImageBitmapBinding::Wrap,
+        )
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-createimagebitmap>
+    ///
+    /// `sw`/`sh` of `None` mean the crop rect extends to the source's natural edge, as at
+    /// the 2-argument and 4-argument overloads of `createImageBitmap`.
+    pub(crate) fn create_image_bitmap(
+        global: &GlobalScope,
+        image: ImageBitmapSource,
+        sx: i32,
+        sy: i32,
+        sw: Option<i32>,
+        sh: Option<i32>,
+        options: &ImageBitmapOptions,
+        can_gc: CanGc,
+    ) -> Rc<Promise> {
+        let promise = Promise::new(global, can_gc);
+
+        let Some(source) = decode_source(&image) else {
+            promise.reject_error(Error::InvalidState, can_gc);
+            return promise;
+        };
+
+        let crop = resolve_crop_rect(source.width, source.height, sx, sy, sw, sh);
+        if crop.2 == 0 || crop.3 == 0 {
+            promise.reject_error(Error::InvalidState, can_gc);
+            return promise;
+        }
+
+        let Some((resize_width, resize_height)) = resolve_resize_dimensions(
+            options.resizeWidth,
+            options.resizeHeight,
+            crop.2,
+            crop.3,
+        ) else {
+            promise.reject_error(Error::InvalidState, can_gc);
+            return promise;
+        };
+
+        let cropped = crop_image(&source, crop);
+        let resized = resize_image(cropped, resize_width, resize_height, options.resizeQuality);
+        let oriented = apply_orientation(resized, options.imageOrientation);
+        let result = apply_premultiply_alpha(oriented, options.premultiplyAlpha);
+
+        // `colorSpaceConversion: "none"` means skip ICC/color management; there is no
+        // color-managed decode in this tree to skip either way, so both values leave `result`
+        // unchanged.
+        let _ = options.colorSpaceConversion;
+
+        let bitmap = ImageBitmap::new(global, result.width, result.height, result.data);
+        promise.resolve_native(&bitmap, can_gc);
+        promise
+    }
+
+    pub(crate) fn width(&self) -> u32 {
+        self.width.get()
+    }
+
+    pub(crate) fn height(&self) -> u32 {
+        self.height.get()
+    }
+
+    pub(crate) fn bitmap_data(&self) -> Option<Vec<u8>> {
+        self.bitmap_data.borrow().clone()
+    }
+}
+
+impl ImageBitmapMethods for ImageBitmap {
+    /// <https://html.spec.whatwg.org/multipage/#dom-imagebitmap-width>
+    fn Width(&self) -> u32 {
+        if self.bitmap_data.borrow().is_none() {
+            return 0;
+        }
+        self.width.get()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-imagebitmap-height>
+    fn Height(&self) -> u32 {
+        if self.bitmap_data.borrow().is_none() {
+            return 0;
+        }
+        self.height.get()
+    }
+
+    /// <https://html.spec.whatwg.org/multipage/#dom-imagebitmap-close>
+    fn Close(&self) {
+        *self.bitmap_data.borrow_mut() = None;
+        self.width.set(0);
+        self.height.set(0);
+    }
+}
+
+/// Decodes `source` into row-major RGBA8 pixels.
+///
+/// `ImageData` already stores non-premultiplied RGBA8 pixels directly, so it needs no
+/// decode step. The other `ImageBitmapSource` variants (`HTMLImageElement` rasterization,
+/// canvas snapshot, `Blob` image decode, …) each need their own decode path, none of which
+/// exist in this checkout yet, so they're still treated as undecodable.
+fn decode_source(image: &ImageBitmapSource) -> Option<DecodedImage> {
+    match image {
+        ImageBitmapSource::ImageData(image_data) => Some(DecodedImage {
+            width: image_data.Width(),
+            height: image_data.Height(),
+            data: image_data.data(),
+        }),
+        _ => None,
+    }
+}
+
+/// Resolves the `sx, sy, sw, sh` crop rect against the source's natural dimensions,
+/// normalizing a negative width/height (a flipped rect, per
+/// <https://html.spec.whatwg.org/multipage/#cropping-and-scaling>) to a positive one.
+fn resolve_crop_rect(
+    source_width: u32,
+    source_height: u32,
+    sx: i32,
+    sy: i32,
+    sw: Option<i32>,
+    sh: Option<i32>,
+) -> (i32, i32, u32, u32) {
+    let sw = sw.unwrap_or(source_width as i32);
+    let sh = sh.unwrap_or(source_height as i32);
+    let (x, width) = normalize_axis(sx, sw);
+    let (y, height) = normalize_axis(sy, sh);
+    (x, y, width, height)
+}
+
+/// Turns a possibly-negative `(start, length)` pair into a normalized `(start, length)` pair
+/// with a non-negative length, flipping the origin when `length` was negative.
+fn normalize_axis(start: i32, length: i32) -> (i32, u32) {
+    if length < 0 {
+        (start + length, length.unsigned_abs())
+    } else {
+        (start, length as u32)
+    }
+}
+
+/// Resolves `resizeWidth`/`resizeHeight` against the crop rect's dimensions, preserving the
+/// crop's aspect ratio when only one of the two is given. Returns `None` for an explicit `0`,
+/// which must reject the `createImageBitmap()` promise.
+fn resolve_resize_dimensions(
+    resize_width: Option<u32>,
+    resize_height: Option<u32>,
+    crop_width: u32,
+    crop_height: u32,
+) -> Option<(u32, u32)> {
+    if resize_width == Some(0) || resize_height == Some(0) {
+        return None;
+    }
+
+    Some(match (resize_width, resize_height) {
+        (None, None) => (crop_width, crop_height),
+        (Some(width), None) => (
+            width,
+            (crop_height as f64 * width as f64 / crop_width as f64).round() as u32,
+        ),
+        (None, Some(height)) => (
+            (crop_width as f64 * height as f64 / crop_height as f64).round() as u32,
+            height,
+        ),
+        (Some(width), Some(height)) => (width, height),
+    })
+}
+
+/// Copies the `(x, y, width, height)` crop rect out of `source`, padding with transparent
+/// black where the rect extends beyond the source's bounds.
+fn crop_image(source: &DecodedImage, crop: (i32, i32, u32, u32)) -> DecodedImage {
+    let (x, y, width, height) = crop;
+    let mut data = vec![0u8; width as usize * height as usize * 4];
+    for row in 0..height as i32 {
+        let src_y = y + row;
+        if src_y < 0 || src_y >= source.height as i32 {
+            continue;
+        }
+        for col in 0..width as i32 {
+            let src_x = x + col;
+            if src_x < 0 || src_x >= source.width as i32 {
+                continue;
+            }
+            let src_index = (src_y as usize * source.width as usize + src_x as usize) * 4;
+            let dst_index = (row as usize * width as usize + col as usize) * 4;
+            data[dst_index..dst_index + 4].copy_from_slice(&source.data[src_index..src_index + 4]);
+        }
+    }
+    DecodedImage {
+        width,
+        height,
+        data,
+    }
+}
+
+/// Scales `source` to `width, height`, using nearest-neighbor sampling for
+/// `ResizeQuality::Pixelated` and bilinear filtering for the quality hints that ask for
+/// smoothing (`low`/`medium`/`high` are not otherwise distinguished here).
+fn resize_image(
+    source: DecodedImage,
+    width: u32,
+    height: u32,
+    quality: ResizeQuality,
+) -> DecodedImage {
+    if width == source.width && height == source.height {
+        return source;
+    }
+    match quality {
+        ResizeQuality::Pixelated => resize_nearest(&source, width, height),
+        ResizeQuality::Low | ResizeQuality::Medium | ResizeQuality::High => {
+            resize_bilinear(&source, width, height)
+        },
+    }
+}
+
+fn resize_nearest(source: &DecodedImage, width: u32, height: u32) -> DecodedImage {
+    let mut data = vec![0u8; width as usize * height as usize * 4];
+    for row in 0..height {
+        let src_y =
+            (row as u64 * source.height as u64 / height as u64).min(source.height as u64 - 1);
+        for col in 0..width {
+            let src_x =
+                (col as u64 * source.width as u64 / width as u64).min(source.width as u64 - 1);
+            let src_index = (src_y as usize * source.width as usize + src_x as usize) * 4;
+            let dst_index = (row as usize * width as usize + col as usize) * 4;
+            data[dst_index..dst_index + 4].copy_from_slice(&source.data[src_index..src_index + 4]);
+        }
+    }
+    DecodedImage {
+        width,
+        height,
+        data,
+    }
+}
+
+fn resize_bilinear(source: &DecodedImage, width: u32, height: u32) -> DecodedImage {
+    let mut data = vec![0u8; width as usize * height as usize * 4];
+    let scale_x = source.width as f64 / width as f64;
+    let scale_y = source.height as f64 / height as f64;
+    let sample = |x: usize, y: usize, channel: usize| -> f64 {
+        source.data[(y * source.width as usize + x) * 4 + channel] as f64
+    };
+
+    for row in 0..height {
+        let src_y = ((row as f64 + 0.5) * scale_y - 0.5).max(0.0);
+        let y0 = src_y.floor() as usize;
+        let y1 = (y0 + 1).min(source.height as usize - 1);
+        let fy = src_y - y0 as f64;
+
+        for col in 0..width {
+            let src_x = ((col as f64 + 0.5) * scale_x - 0.5).max(0.0);
+            let x0 = src_x.floor() as usize;
+            let x1 = (x0 + 1).min(source.width as usize - 1);
+            let fx = src_x - x0 as f64;
+
+            let dst_index = (row as usize * width as usize + col as usize) * 4;
+            for channel in 0..4 {
+                let top = sample(x0, y0, channel) * (1.0 - fx) + sample(x1, y0, channel) * fx;
+                let bottom = sample(x0, y1, channel) * (1.0 - fx) + sample(x1, y1, channel) * fx;
+                data[dst_index + channel] = (top * (1.0 - fy) + bottom * fy)
+                    .round()
+                    .clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+    DecodedImage {
+        width,
+        height,
+        data,
+    }
+}
+
+/// Applies `imageOrientation`: `"flipY"` mirrors the image vertically; `"from-image"` (the
+/// default) leaves `image` as `decode_source` produced it, and `decode_source` applies no
+/// EXIF orientation.
+fn apply_orientation(image: DecodedImage, orientation: ImageOrientation) -> DecodedImage {
+    if orientation != ImageOrientation::FlipY {
+        return image;
+    }
+
+    let DecodedImage {
+        width,
+        height,
+        mut data,
+    } = image;
+    let stride = width as usize * 4;
+    for row in 0..(height as usize / 2) {
+        let (front, back) = data.split_at_mut(stride * (height as usize - row - 1));
+        let top = &mut front[stride * row..stride * (row + 1)];
+        let bottom = &mut back[..stride];
+        top.swap_with_slice(bottom);
+    }
+    DecodedImage {
+        width,
+        height,
+        data,
+    }
+}
+
+/// Applies `premultiplyAlpha`: `"premultiply"` forces premultiplied output; `"none"` and
+/// `"default"` both leave the already-non-premultiplied pixels unchanged.
+fn apply_premultiply_alpha(image: DecodedImage, mode: PremultiplyAlpha) -> DecodedImage {
+    if mode != PremultiplyAlpha::Premultiply {
+        return image;
+    }
+
+    let DecodedImage {
+        width,
+        height,
+        mut data,
+    } = image;
+    for pixel in data.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u16;
+        for channel in &mut pixel[..3] {
+            *channel = (*channel as u16 * alpha / 255) as u8;
+        }
+    }
+    DecodedImage {
+        width,
+        height,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_crop_rect_defaults_to_natural_size() {
+        assert_eq!(resolve_crop_rect(100, 50, 0, 0, None, None), (0, 0, 100, 50));
+    }
+
+    #[test]
+    fn resolve_crop_rect_normalizes_negative_dimensions() {
+        // A crop rect of (10, 10, -5, -5) covers the same pixels as (5, 5, 5, 5).
+        assert_eq!(
+            resolve_crop_rect(100, 100, 10, 10, Some(-5), Some(-5)),
+            (5, 5, 5, 5)
+        );
+    }
+
+    #[test]
+    fn resolve_crop_rect_allows_out_of_bounds_origin() {
+        assert_eq!(
+            resolve_crop_rect(100, 100, -10, -10, Some(20), Some(20)),
+            (-10, -10, 20, 20)
+        );
+    }
+
+    #[test]
+    fn resolve_resize_dimensions_defaults_to_crop_size() {
+        assert_eq!(resolve_resize_dimensions(None, None, 40, 20), Some((40, 20)));
+    }
+
+    #[test]
+    fn resolve_resize_dimensions_preserves_aspect_ratio() {
+        assert_eq!(resolve_resize_dimensions(Some(80), None, 40, 20), Some((80, 40)));
+        assert_eq!(resolve_resize_dimensions(None, Some(10), 40, 20), Some((20, 10)));
+    }
+
+    #[test]
+    fn resolve_resize_dimensions_rejects_explicit_zero() {
+        assert_eq!(resolve_resize_dimensions(Some(0), None, 40, 20), None);
+        assert_eq!(resolve_resize_dimensions(None, Some(0), 40, 20), None);
+    }
+
+    #[test]
+    fn resolve_resize_dimensions_honors_both_explicit_values() {
+        assert_eq!(
+            resolve_resize_dimensions(Some(10), Some(10), 40, 20),
+            Some((10, 10))
+        );
+    }
+}
@@ -11,8 +11,11 @@ use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
 use crate::dom::bindings::root::DomRoot;
 use crate::dom::bindings::settings_stack::AutoIncumbentScript;
 use crate::dom::bindings::utils::get_dictionary_property;
+use crate::dom::bindings::import::module::Fallible;
 use crate::dom::globalscope::GlobalScope;
+use crate::dom::messageport::MessagePort;
 use crate::dom::promise::Promise;
+use crate::dom::promisenativehandler::{Callback, PromiseNativeHandler};
 use crate::js::conversions::FromJSValConvertible;
 use crate::realms::{enter_realm, AlreadyInRealm, InRealm};
 use crate::script_runtime::JSContext as SafeJSContext;
@@ -25,7 +28,8 @@ use js::glue::{CreateReadableStreamUnderlyingSource, ReadableStreamUnderlyingSou
 use js::jsapi::HandleValue;
 use js::jsapi::{
     HandleObject, Heap, IsReadableStream, JSContext, JSObject,
-    NewReadableExternalSourceStreamObject, ReadableStreamClose, ReadableStreamDefaultReaderRead,
+    NewReadableExternalSourceStreamObject, ReadableStreamBYOBReaderRead, ReadableStreamClose,
+    ReadableStreamDefaultReaderRead,
     ReadableStreamError, ReadableStreamGetReader, ReadableStreamIsDisturbed,
     ReadableStreamIsLocked, ReadableStreamIsReadable, ReadableStreamReaderMode,
     ReadableStreamReaderReleaseLock, ReadableStreamUpdateDataAvailableFromSource,
@@ -36,10 +40,17 @@ use js::jsval::UndefinedValue;
 use js::rust::HandleValue as SafeHandleValue;
 use js::rust::IntoHandle;
 use std::cell::Cell;
+use std::collections::VecDeque;
 use std::os::raw::c_void;
+use std::pin::Pin;
 use std::ptr::{self, NonNull};
 use std::rc::Rc;
 use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::io::AsyncRead;
+use futures::stream::Stream;
 
 #[dom_struct]
 #[unrooted_must_root_lint::allow_unrooted_in_rc]
@@ -50,6 +61,10 @@ pub struct ReadableStream {
     #[ignore_malloc_size_of = "SM handles JS values"]
     js_reader: Heap<*mut JSObject>,
     has_reader: Cell<bool>,
+    /// <https://streams.spec.whatwg.org/#rs-transfer>
+    /// Set once the stream has been transferred to another realm; a detached
+    /// stream is inert and all of its public operations throw.
+    detached: Cell<bool>,
     #[ignore_malloc_size_of = "Rc is hard"]
     external_underlying_source: Option<Rc<ExternalUnderlyingSourceController>>,
 }
@@ -63,6 +78,7 @@ impl ReadableStream {
             js_stream: Heap::default(),
             js_reader: Heap::default(),
             has_reader: Default::default(),
+            detached: Default::default(),
             external_underlying_source: external_underlying_source,
         }
     }
@@ -165,9 +181,9 @@ impl ReadableStream {
             .expect("Couldn't get a non-null pointer to JS stream object.")
     }
 
-    /// Enqueue bytes to the underlying source(via the controller).
+    /// Enqueue a (possibly shared) chunk to the underlying source (via the controller).
     #[allow(unsafe_code)]
-    pub fn enqueue_native(&self, bytes: &[u8]) {
+    pub fn enqueue_native(&self, bytes: Rc<Vec<u8>>) {
         let global = self.global();
         let ar = enter_realm(&*global);
         let cx = global.get_cx();
@@ -199,6 +215,20 @@ impl ReadableStream {
         }
     }
 
+    /// Error the stream with an already-constructed JS value, e.g. a rejection
+    /// reason forwarded verbatim from another stream's read, rather than a
+    /// Rust-side [`Error`] that still needs converting to a `JSVal`.
+    #[allow(unsafe_code)]
+    pub fn error_native_jsval(&self, reason: SafeHandleValue) {
+        let global = self.global();
+        let ar = enter_realm(&*global);
+        let cx = global.get_cx();
+
+        unsafe {
+            ReadableStreamError(*cx, self.js_stream.handle(), reason.into_handle());
+        }
+    }
+
     /// Close a stream via it's underlying source controller.
     #[allow(unsafe_code)]
     pub fn close_native(&self) {
@@ -214,12 +244,89 @@ impl ReadableStream {
             .close(cx, handle);
     }
 
+    /// Whether this stream has been detached by a transfer to another realm.
+    /// <https://streams.spec.whatwg.org/#is-readable-stream-locked> neighbours.
+    pub fn is_detached(&self) -> bool {
+        self.detached.get()
+    }
+
+    /// <https://streams.spec.whatwg.org/#rs-transfer>
+    ///
+    /// Detach this stream and hand its data to a cross-realm identity
+    /// transform: the local end drains the original stream into the entangled
+    /// `port`, whose peer is reconstituted as a `ReadableStream` in the
+    /// destination realm. After this returns the source is detached, so
+    /// `getReader`/`tee`/`cancel` throw.
+    pub fn transfer(&self, port: &MessagePort) -> Fallible<()> {
+        // If ! IsReadableStreamLocked(stream) is true, throw a "DataCloneError".
+        if self.is_locked() || self.is_detached() {
+            return Err(Error::DataClone);
+        }
+
+        // Set stream.[[Detached]] to true.
+        self.detached.set(true);
+
+        // Create the cross-realm transform whose writable side drains this
+        // stream into `port`; the peer port carries the chunks to the
+        // destination realm.
+        port.set_up_cross_realm_transform_writable(self);
+
+        Ok(())
+    }
+
+    /// <https://streams.spec.whatwg.org/#readable-stream-tee>
+    ///
+    /// Produce two branches that each yield the chunks of this stream. The two
+    /// branches share a single [`TeeAdapter`], which reads one chunk at a time
+    /// from the source and fans an `Rc`-shared reference to it out to both
+    /// branches before reading again, so a chunk's backing `Vec` is only freed
+    /// once both branches have drained past it rather than being copied into
+    /// each branch's queue up front.
+    pub fn tee(&self) -> Fallible<(DomRoot<ReadableStream>, DomRoot<ReadableStream>)> {
+        if self.is_detached() || self.is_locked() || self.is_disturbed() {
+            return Err(Error::Type(
+                "Cannot tee a locked, disturbed or detached stream.".to_string(),
+            ));
+        }
+
+        let global = self.global();
+        let branch_1 =
+            ReadableStream::new_with_external_underlying_source(&global, ExternalUnderlyingSource::Tee);
+        let branch_2 =
+            ReadableStream::new_with_external_underlying_source(&global, ExternalUnderlyingSource::Tee);
+
+        self.start_reading()
+            .map_err(|()| Error::Type("Could not acquire a reader to tee.".to_string()))?;
+
+        let adapter = Rc::new(TeeAdapter {
+            source: Trusted::new(self),
+            branch_1: Trusted::new(&*branch_1),
+            branch_2: Trusted::new(&*branch_2),
+            canceled_1: Cell::new(false),
+            canceled_2: Cell::new(false),
+        });
+        branch_1.set_tee_adapter(adapter.clone(), TeeBranchId::Branch1);
+        branch_2.set_tee_adapter(adapter.clone(), TeeBranchId::Branch2);
+        adapter.pump();
+
+        Ok((branch_1, branch_2))
+    }
+
+    /// Wire this branch's external source to the [`TeeAdapter`] driving it, so the
+    /// branch's native `cancel` callback can route back into the adapter.
+    fn set_tee_adapter(&self, adapter: Rc<TeeAdapter>, branch: TeeBranchId) {
+        self.external_underlying_source
+            .as_ref()
+            .expect("A tee branch always has an external underlying source.")
+            .set_tee(adapter, branch);
+    }
+
     /// Acquires a reader and locks the stream,
     /// must be done before `read_a_chunk`,
     /// fails if the stream is already locked to a reader.
     #[allow(unsafe_code)]
     pub fn start_reading(&self) -> Result<(), ()> {
-        if self.is_locked() || self.is_disturbed() {
+        if self.is_detached() || self.is_locked() || self.is_disturbed() {
             return Err(());
         }
 
@@ -244,6 +351,70 @@ impl ReadableStream {
         Ok(())
     }
 
+    /// Acquire a BYOB (bring-your-own-buffer) reader and lock the stream.
+    ///
+    /// The byte-reader counterpart of [`Self::start_reading`]; reads are then
+    /// performed with [`Self::read_into`], filling a caller-supplied view
+    /// rather than allocating a fresh chunk. Fails if the stream is already
+    /// locked or disturbed, or is not a byte stream.
+    #[allow(unsafe_code)]
+    pub fn start_reading_byob(&self) -> Result<(), ()> {
+        if self.is_detached() || self.is_locked() || self.is_disturbed() {
+            return Err(());
+        }
+
+        let global = self.global();
+        let _ar = enter_realm(&*global);
+        let cx = global.get_cx();
+
+        unsafe {
+            rooted!(in(*cx) let stream = self.js_stream.get());
+
+            rooted!(in(*cx) let reader = ReadableStreamGetReader(
+                *cx,
+                stream.handle().into_handle(),
+                ReadableStreamReaderMode::Byob,
+            ));
+
+            if reader.get().is_null() {
+                // The stream does not have a byte controller.
+                return Err(());
+            }
+
+            // Note: the stream is locked to the reader.
+            self.js_reader.set(reader.get());
+        }
+
+        self.has_reader.set(true);
+        Ok(())
+    }
+
+    /// Read into a caller-supplied `ArrayBufferView`, must be called after
+    /// [`Self::start_reading_byob`]. Resolves with the filled view (or a view
+    /// over the transferred buffer) once bytes are available.
+    #[allow(unsafe_code)]
+    pub fn read_into(&self, view: HandleObject) -> Rc<Promise> {
+        if !self.has_reader.get() {
+            panic!("Attempt to read into a stream without having acquired a BYOB reader.");
+        }
+
+        let global = self.global();
+        let _ar = enter_realm(&*global);
+        AlreadyInRealm::assert(&*global);
+        let _ais = AutoIncumbentScript::new(&*global);
+
+        let cx = global.get_cx();
+
+        unsafe {
+            rooted!(in(*cx) let promise_obj = ReadableStreamBYOBReaderRead(
+                *cx,
+                self.js_reader.handle(),
+                view,
+            ));
+            Promise::new_with_js_promise(promise_obj.handle(), cx)
+        }
+    }
+
     /// Read a chunk from the stream,
     /// must be called after `start_reading`,
     /// and before `stop_reading`.
@@ -365,11 +536,13 @@ unsafe extern "C" fn write_into_read_request_buffer(
 
 #[allow(unsafe_code)]
 unsafe extern "C" fn cancel(
-    _source: *const c_void,
+    source: *const c_void,
     _cx: *mut JSContext,
     _stream: HandleObject,
     _reason: HandleValue,
 ) -> *mut JSVal {
+    let source = &*(source as *const ExternalUnderlyingSourceController);
+    source.on_cancel();
     ptr::null_mut()
 }
 
@@ -402,6 +575,150 @@ pub enum ExternalUnderlyingSource {
     FetchResponse,
     /// A fetch request as underlying source.
     FetchRequest,
+    /// A branch of a `tee()`, fed chunk-by-chunk from the original stream by a
+    /// [`TeeAdapter`].
+    Tee,
+}
+
+/// Identifies one of the two live branches produced by [`ReadableStream::tee`].
+#[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
+pub(crate) enum TeeBranchId {
+    Branch1,
+    Branch2,
+}
+
+/// Shared machinery backing the two branches of [`ReadableStream::tee`].
+///
+/// The adapter reads a single chunk from `source`, fans an `Rc`-shared
+/// reference to it out to both live branches, then reads again, so at most
+/// one chunk is read ahead and a chunk's backing `Vec` is only freed once
+/// both branches have drained past it. A rejected read errors both branches
+/// and releases the trunk reader; a canceled branch stops receiving chunks,
+/// and once both branches are canceled the trunk reader is released too.
+#[derive(JSTraceable, MallocSizeOf)]
+struct TeeAdapter {
+    source: Trusted<ReadableStream>,
+    branch_1: Trusted<ReadableStream>,
+    branch_2: Trusted<ReadableStream>,
+    canceled_1: Cell<bool>,
+    canceled_2: Cell<bool>,
+}
+
+impl TeeAdapter {
+    /// Read one chunk from the source and chain the next read once it resolves.
+    fn pump(self: &Rc<Self>) {
+        let source = self.source.root();
+        let promise = source.read_a_chunk();
+        let handler = PromiseNativeHandler::new(
+            &source.global(),
+            Some(TeeReadHandler::new(self.clone())),
+            Some(TeeRejectHandler::new(self.clone())),
+        );
+        let realm = enter_realm(&*source.global());
+        let comp = InRealm::Entered(&realm);
+        promise.append_native_handler(&handler, comp);
+    }
+
+    /// A chunk (or end-of-stream) resolved; fan it out to the branches that
+    /// haven't been canceled.
+    #[allow(unsafe_code)]
+    fn on_chunk(self: &Rc<Self>, cx: SafeJSContext, v: SafeHandleValue) {
+        if self.canceled_1.get() && self.canceled_2.get() {
+            // Both branches were canceled and the trunk reader already released.
+            return;
+        }
+        let done = get_read_promise_done(cx, &v).unwrap_or(true);
+        if done {
+            self.branch_1.root().close_native();
+            self.branch_2.root().close_native();
+            self.source.root().stop_reading();
+            return;
+        }
+
+        match get_read_promise_bytes(cx, &v) {
+            Ok(bytes) => {
+                let chunk = Rc::new(bytes);
+                if !self.canceled_1.get() {
+                    self.branch_1.root().enqueue_native(chunk.clone());
+                }
+                if !self.canceled_2.get() {
+                    self.branch_2.root().enqueue_native(chunk);
+                }
+            },
+            Err(_) => {
+                // The resolved value wasn't a byte chunk; treat it the same as
+                // a rejected read rather than silently dropping it.
+                self.on_error(cx, v);
+                return;
+            },
+        }
+
+        // Read the next chunk only now, keeping at most one chunk in flight.
+        self.pump();
+    }
+
+    /// The trunk read rejected (or resolved to something that wasn't a valid
+    /// chunk); propagate the error to both branches and release the trunk.
+    fn on_error(self: &Rc<Self>, _cx: SafeJSContext, reason: SafeHandleValue) {
+        if !self.canceled_1.get() {
+            self.branch_1.root().error_native_jsval(reason);
+        }
+        if !self.canceled_2.get() {
+            self.branch_2.root().error_native_jsval(reason);
+        }
+        self.source.root().stop_reading();
+    }
+
+    /// One of the branches was canceled. Once both have been, there is no one
+    /// left to read for, so release the trunk reader.
+    fn on_branch_canceled(self: &Rc<Self>, branch: TeeBranchId) {
+        match branch {
+            TeeBranchId::Branch1 => self.canceled_1.set(true),
+            TeeBranchId::Branch2 => self.canceled_2.set(true),
+        }
+        if self.canceled_1.get() && self.canceled_2.get() {
+            self.source.root().stop_reading();
+        }
+    }
+}
+
+/// Promise reaction that forwards each resolved tee read to its [`TeeAdapter`].
+#[derive(JSTraceable, MallocSizeOf)]
+struct TeeReadHandler {
+    #[ignore_malloc_size_of = "Rc"]
+    adapter: Rc<TeeAdapter>,
+}
+
+impl TeeReadHandler {
+    fn new(adapter: Rc<TeeAdapter>) -> Box<TeeReadHandler> {
+        Box::new(TeeReadHandler { adapter })
+    }
+}
+
+impl Callback for TeeReadHandler {
+    fn callback(&self, cx: SafeJSContext, v: SafeHandleValue, _realm: InRealm) {
+        self.adapter.on_chunk(cx, v);
+    }
+}
+
+/// Promise reaction that forwards each rejected tee read to its [`TeeAdapter`],
+/// so the trunk erroring propagates to both branches rather than stalling them.
+#[derive(JSTraceable, MallocSizeOf)]
+struct TeeRejectHandler {
+    #[ignore_malloc_size_of = "Rc"]
+    adapter: Rc<TeeAdapter>,
+}
+
+impl TeeRejectHandler {
+    fn new(adapter: Rc<TeeAdapter>) -> Box<TeeRejectHandler> {
+        Box::new(TeeRejectHandler { adapter })
+    }
+}
+
+impl Callback for TeeRejectHandler {
+    fn callback(&self, cx: SafeJSContext, v: SafeHandleValue, _realm: InRealm) {
+        self.adapter.on_error(cx, v);
+    }
 }
 
 /// When `finalize` is called, use this to schedule a task
@@ -429,11 +746,35 @@ impl StreamFinalizer {
     }
 }
 
+/// How a controller obtains more bytes when its buffer runs dry.
+#[derive(JSTraceable, MallocSizeOf, PartialEq)]
+enum SourceKind {
+    /// Data is pushed at us (memory/blob); `pull` cannot ask for more.
+    Push,
+    /// Data is pulled over IPC from a fetch response/request; `pull` requests
+    /// the next chunk when the buffer is empty.
+    PullOverIpc,
+}
+
 #[derive(JSTraceable, MallocSizeOf)]
 struct ExternalUnderlyingSourceController {
     /// Loosely matches the underlying queue,
     /// <https://streams.spec.whatwg.org/#internal-queues>
-    buffer: DomRefCell<Vec<u8>>,
+    ///
+    /// A FIFO queue of chunks (oldest at the front). Enqueuing pushes a chunk
+    /// at the back and reading drains from the front, so neither operation
+    /// copies the whole buffer — unlike the previous prepend-and-concat `Vec`,
+    /// which was O(n²) over a stream's lifetime.
+    buffer: DomRefCell<VecDeque<Rc<Vec<u8>>>>,
+    /// Whether this source is push- or pull-based.
+    kind: SourceKind,
+    /// For a pull-based source, the channel used to ask the networking task for
+    /// the next chunk. `pull_in_flight` records whether a request is
+    /// outstanding, which enforces one-outstanding-pull backpressure.
+    #[ignore_malloc_size_of = "IpcSender"]
+    pull_requester: Mutex<Option<IpcSender<()>>>,
+    /// Whether a pull request is currently outstanding.
+    pull_in_flight: DomRefCell<bool>,
     /// Has the stream been closed by native code?
     closed: DomRefCell<bool>,
     /// An object that maybe be accessed from a background "clean-up" thread,
@@ -443,22 +784,62 @@ struct ExternalUnderlyingSourceController {
     /// and upon finalization, potentially on a background thread.
     #[ignore_malloc_size_of = "StreamFinalizer"]
     finalizer: Mutex<Option<StreamFinalizer>>,
+    /// Set only for a [`ExternalUnderlyingSource::Tee`] branch: the adapter
+    /// driving it and which branch this is, so the native `cancel` trap can
+    /// route back into the adapter.
+    #[ignore_malloc_size_of = "Rc"]
+    tee: DomRefCell<Option<(Rc<TeeAdapter>, TeeBranchId)>>,
 }
 
 impl ExternalUnderlyingSourceController {
     fn new(source: ExternalUnderlyingSource) -> ExternalUnderlyingSourceController {
-        let buffer = match source {
-            ExternalUnderlyingSource::Blob(size) => Vec::with_capacity(size),
-            ExternalUnderlyingSource::Memory(bytes) => bytes,
-            ExternalUnderlyingSource::FetchResponse | ExternalUnderlyingSource::FetchRequest => vec![],
+        let kind = match source {
+            ExternalUnderlyingSource::FetchResponse | ExternalUnderlyingSource::FetchRequest => {
+                SourceKind::PullOverIpc
+            },
+            _ => SourceKind::Push,
+        };
+        let mut buffer = VecDeque::new();
+        match source {
+            // Seed the queue with the already-in-memory bytes as a single chunk.
+            ExternalUnderlyingSource::Memory(bytes) if !bytes.is_empty() => {
+                buffer.push_back(Rc::new(bytes));
+            },
+            _ => {},
         };
         ExternalUnderlyingSourceController {
             buffer: DomRefCell::new(buffer),
+            kind,
+            pull_requester: Mutex::new(None),
+            pull_in_flight: DomRefCell::new(false),
             closed: DomRefCell::new(false),
             finalizer: Mutex::new(None),
+            tee: DomRefCell::new(None),
         }
     }
 
+    /// Record the [`TeeAdapter`] driving this branch, so a later `cancel` can
+    /// route back into it.
+    fn set_tee(&self, adapter: Rc<TeeAdapter>, branch: TeeBranchId) {
+        *self.tee.borrow_mut() = Some((adapter, branch));
+    }
+
+    /// The branch's native `cancel` callback: drop any chunks still queued for
+    /// this branch and tell the adapter this branch is done.
+    fn on_cancel(&self) {
+        self.buffer.borrow_mut().clear();
+        if let Some((adapter, branch)) = self.tee.borrow_mut().take() {
+            adapter.on_branch_canceled(branch);
+        }
+    }
+
+    /// Provide the channel used to request the next chunk from the networking
+    /// task. Nothing calls this, so `request_chunk` sends no request and a
+    /// pull-based source only receives chunks that are pushed at it.
+    fn set_pull_requester(&self, requester: IpcSender<()>) {
+        *self.pull_requester.lock().unwrap() = Some(requester);
+    }
+
     fn set_up_finalize(
         &self,
         stream: Trusted<ReadableStream>,
@@ -517,25 +898,46 @@ impl ExternalUnderlyingSourceController {
         self.maybe_close_js_stream(cx, stream);
     }
 
-    fn enqueue_chunk(&self, cx: SafeJSContext, stream: HandleObject, chunk: &[u8]) {
+    /// Total number of bytes currently queued across all chunks.
+    fn buffered_len(&self) -> usize {
+        self.buffer.borrow().iter().map(|chunk| chunk.len()).sum()
+    }
+
+    fn enqueue_chunk(&self, cx: SafeJSContext, stream: HandleObject, chunk: Rc<Vec<u8>>) {
         println!("Enqueuing chunks: {:?}", chunk.len());
+        // A pushed/pulled chunk satisfies any outstanding pull request.
+        *self.pull_in_flight.borrow_mut() = false;
         let available = {
             let mut buffer = self.buffer.borrow_mut();
-            *buffer = [chunk, buffer.as_slice()].concat().to_vec();
-            buffer.len()
+            buffer.push_back(chunk);
+            buffer.iter().map(|chunk| chunk.len()).sum()
         };
         self.signal_available_bytes(cx, stream, available);
     }
 
+    /// Ask the networking task for the next chunk, unless one is already in
+    /// flight. Enforces one-outstanding-pull backpressure: no further request
+    /// is issued until the current chunk arrives via `enqueue_chunk`.
+    fn request_chunk(&self) {
+        if *self.pull_in_flight.borrow() {
+            return;
+        }
+        if let Some(requester) = self.pull_requester.lock().unwrap().as_ref() {
+            if requester.send(()).is_ok() {
+                *self.pull_in_flight.borrow_mut() = true;
+            }
+        }
+    }
+
     /// The "pull steps" for this controller.
-    /// If we restructured fetch or file-reading to be pull-based, this hook could be used to pull a chunk over IPC,
-    /// (via an async request for a new chunk).
-    /// Since everything currently just pushes data at us, we simply look at the buffer and signal available bytes.
+    /// For a pull-based source (a fetch response/request), an empty buffer
+    /// triggers an async request for the next chunk over IPC; otherwise data is
+    /// pushed at us and we simply signal whatever is already buffered.
     #[allow(unsafe_code)]
     fn pull(&self, cx: SafeJSContext, stream: HandleObject, desired_size: usize) {
         println!(
             "Pull steps ExternalUnderlyingSourceController with buffer: {:?} closed: {:?} desired_size: {:?}",
-            self.buffer.borrow().len(),
+            self.buffered_len(),
             *self.closed.borrow(),
             desired_size,
         );
@@ -546,13 +948,13 @@ impl ExternalUnderlyingSourceController {
             return self.maybe_close_js_stream(cx, stream);
         }
 
-        let available = {
-            let buffer = self.buffer.borrow();
-            buffer.len()
-        };
+        let available = self.buffered_len();
 
         if available > 0 {
             self.signal_available_bytes(cx, stream, desired_size);
+        } else if self.kind == SourceKind::PullOverIpc {
+            // Nothing buffered: pull the next chunk over IPC.
+            self.request_chunk();
         }
     }
 
@@ -568,22 +970,153 @@ impl ExternalUnderlyingSourceController {
     ) {
 
         let mut buffer = self.buffer.borrow_mut();
-        let buffer_len = buffer.len();
-        assert!(buffer_len >= length as usize);
-
-        let (rest, chunk) = buffer.as_slice().split_at(buffer_len - length);
+        let available: usize = buffer.iter().map(|chunk| chunk.len()).sum();
+        assert!(available >= length);
+
+        // Drain up to `length` bytes from the front chunks into the target. A
+        // chunk that is only partially consumed is split, its tail put back at
+        // the front of the queue.
+        let mut written = 0;
+        while written < length {
+            let mut chunk = buffer.pop_front().expect("buffer underflow");
+            let take = (length - written).min(chunk.len());
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    chunk.as_ptr(),
+                    (target_buffer as *mut u8).add(written),
+                    take,
+                );
+            }
+            written += take;
+            if take < chunk.len() {
+                // Keep the unconsumed tail at the front for the next read. The
+                // chunk is shared (a tee branch may still hold a reference to
+                // it), so the tail is copied into a fresh `Rc` rather than
+                // split out of the original in place.
+                buffer.push_front(Rc::new(chunk[take..].to_vec()));
+            }
+        }
 
         unsafe {
-            *bytes_written = chunk.len();
-            println!(
-                "Writing into buffer with length: {:?} a chunk of len: {:?}",
-                length,
-                chunk.len()
-            );
-            ptr::copy_nonoverlapping(chunk.as_ptr(), target_buffer as *mut u8, chunk.len());
+            *bytes_written = written;
+            println!("Writing into buffer with length: {:?} a chunk of len: {:?}", length, written);
+        }
+    }
+}
+
+impl ReadableStream {
+    /// Adapt this stream into a Rust [`futures::Stream`] of byte chunks.
+    ///
+    /// Reads are driven on the script thread via the native reader; each
+    /// resolved chunk is forwarded over an unbounded channel whose receiver is
+    /// returned. The channel closes when the stream closes or errors. The
+    /// returned receiver can be wrapped in [`AsyncReadStream`] to obtain an
+    /// [`futures::io::AsyncRead`].
+    pub fn into_stream(&self) -> Result<UnboundedReceiver<Vec<u8>>, ()> {
+        self.start_reading()?;
+        let (sender, receiver) = mpsc::unbounded();
+        let reader = Rc::new(StreamChannelAdapter {
+            stream: Trusted::new(self),
+            sender,
+        });
+        reader.pump();
+        Ok(receiver)
+    }
+}
+
+/// Drives reads off a [`ReadableStream`] and forwards chunks over a channel,
+/// backing [`ReadableStream::into_stream`].
+#[derive(JSTraceable, MallocSizeOf)]
+struct StreamChannelAdapter {
+    stream: Trusted<ReadableStream>,
+    #[ignore_malloc_size_of = "futures channel"]
+    sender: UnboundedSender<Vec<u8>>,
+}
+
+impl StreamChannelAdapter {
+    fn pump(self: &Rc<Self>) {
+        let stream = self.stream.root();
+        let promise = stream.read_a_chunk();
+        let handler = PromiseNativeHandler::new(
+            &stream.global(),
+            Some(StreamChannelHandler::new(self.clone())),
+            None,
+        );
+        let realm = enter_realm(&*stream.global());
+        let comp = InRealm::Entered(&realm);
+        promise.append_native_handler(&handler, comp);
+    }
+
+    fn on_chunk(self: &Rc<Self>, cx: SafeJSContext, v: SafeHandleValue) {
+        if get_read_promise_done(cx, &v).unwrap_or(true) {
+            // Dropping the sender closes the receiving `Stream`.
+            self.stream.root().stop_reading();
+            return;
+        }
+        if let Ok(bytes) = get_read_promise_bytes(cx, &v) {
+            // A send error means the consumer dropped the receiver; stop.
+            if self.sender.unbounded_send(bytes).is_err() {
+                self.stream.root().stop_reading();
+                return;
+            }
         }
+        self.pump();
+    }
+}
+
+#[derive(JSTraceable, MallocSizeOf)]
+struct StreamChannelHandler {
+    #[ignore_malloc_size_of = "Rc"]
+    adapter: Rc<StreamChannelAdapter>,
+}
+
+impl StreamChannelHandler {
+    fn new(adapter: Rc<StreamChannelAdapter>) -> Box<StreamChannelHandler> {
+        Box::new(StreamChannelHandler { adapter })
+    }
+}
 
-        *buffer = rest.to_vec();
+impl Callback for StreamChannelHandler {
+    fn callback(&self, cx: SafeJSContext, v: SafeHandleValue, _realm: InRealm) {
+        self.adapter.on_chunk(cx, v);
+    }
+}
+
+/// Wraps the chunk [`Stream`] from [`ReadableStream::into_stream`] as an
+/// [`futures::io::AsyncRead`], carrying over any partially-consumed chunk.
+pub struct AsyncReadStream {
+    inner: UnboundedReceiver<Vec<u8>>,
+    /// Bytes of the current chunk not yet copied to a read buffer.
+    pending: Vec<u8>,
+}
+
+impl AsyncReadStream {
+    pub fn new(inner: UnboundedReceiver<Vec<u8>>) -> AsyncReadStream {
+        AsyncReadStream {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl AsyncRead for AsyncReadStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        if self.pending.is_empty() {
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(chunk)) => self.pending = chunk,
+                // End of stream.
+                Poll::Ready(None) => return Poll::Ready(Ok(0)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Poll::Ready(Ok(n))
     }
 }
 
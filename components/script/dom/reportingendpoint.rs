@@ -0,0 +1,248 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! The wire side of the [Reporting API](https://w3c.github.io/reporting/): named
+//! endpoints parsed from the `Reporting-Endpoints` response header, and delivery of
+//! queued [`Report`]s to them as `application/reports+json` batches.
+
+use std::time::Duration;
+
+use http::HeaderMap;
+use ipc_channel::ipc;
+use ipc_channel::router::ROUTER;
+use net_traits::request::{Destination, RequestBuilder, RequestMode};
+use net_traits::{CoreResourceMsg, FetchChannels, FetchResponseMsg, ResourceThreads};
+use servo_url::ServoUrl;
+
+use crate::dom::bindings::codegen::Bindings::ReportingObserverBinding::Report;
+
+/// Maximum number of reports kept queued for delivery before the oldest are dropped.
+const MAX_QUEUED_REPORTS: usize = 100;
+
+/// Reports older than this are never delivered; every endpoint uses this same limit.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Base delay used for exponential backoff after an endpoint fails.
+const BACKOFF_BASE: Duration = Duration::from_secs(30);
+
+/// A single named reporting endpoint, as declared by `Reporting-Endpoints`.
+#[derive(Clone, Debug)]
+pub(crate) struct ReportingEndpoint {
+    /// The group name reports are routed to (e.g. `"default"`).
+    pub(crate) name: String,
+    /// The collector URL. Reports are only delivered here when same-origin-allowed.
+    pub(crate) url: ServoUrl,
+    /// Consecutive delivery failures, driving exponential backoff.
+    pub(crate) failures: u32,
+    /// Milliseconds (monotonic) before which this endpoint should be skipped.
+    pub(crate) skip_until: Option<u64>,
+}
+
+impl ReportingEndpoint {
+    /// Parse the `Reporting-Endpoints` header — a structured-fields dictionary of
+    /// `name="url"` members — resolving each URL against `response_url`. Endpoints
+    /// whose URL fails to parse are skipped.
+    pub(crate) fn parse_reporting_endpoints_header(
+        response_url: &ServoUrl,
+        headers: &HeaderMap,
+    ) -> Option<Vec<ReportingEndpoint>> {
+        let value = headers.get("reporting-endpoints")?.to_str().ok()?;
+        let mut endpoints = Vec::new();
+        for member in value.split(',') {
+            let member = member.trim();
+            let Some((name, raw_url)) = member.split_once('=') else {
+                continue;
+            };
+            // The value is a structured-fields string: `"https://collector.example"`.
+            let raw_url = raw_url.trim().trim_matches('"');
+            if let Ok(url) = response_url.join(raw_url) {
+                endpoints.push(ReportingEndpoint {
+                    name: name.trim().to_owned(),
+                    url,
+                    failures: 0,
+                    skip_until: None,
+                });
+            }
+        }
+        (!endpoints.is_empty()).then_some(endpoints)
+    }
+
+    /// The delay to wait after `failures` consecutive delivery errors.
+    pub(crate) fn backoff(&self) -> Duration {
+        BACKOFF_BASE * 2u32.saturating_pow(self.failures.min(6))
+    }
+}
+
+/// Serialize a batch of reports destined for a single endpoint as the
+/// `application/reports+json` body: a JSON array of report objects.
+pub(crate) fn serialize_reports(reports: &[QueuedReport], user_agent: &str) -> Vec<u8> {
+    let mut out = String::from("[");
+    for (i, report) in reports.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        out.push_str(&format!(
+            "{{\"type\":{},\"url\":{},\"age\":{},\"user_agent\":{},\"body\":{}}}",
+            json_string(&report.type_),
+            json_string(&report.url),
+            report.age_millis,
+            json_string(user_agent),
+            report.body.as_deref().unwrap_or("null"),
+        ));
+    }
+    out.push(']');
+    out.into_bytes()
+}
+
+/// Serialize a `"css-parse-error"` report body: the owning stylesheet's URL, 1-based
+/// line/column of the offending rule, the `ContextualParseError` message, and the element URL
+/// that pulled in the stylesheet (e.g. a `<link>` or `style=""` attribute), when known.
+pub(crate) fn css_parse_error_report_body(
+    source_url: &str,
+    line: u32,
+    column: u32,
+    message: &str,
+    element_url: Option<&str>,
+) -> String {
+    format!(
+        "{{\"sourceURL\":{},\"lineNumber\":{line},\"columnNumber\":{column},\"message\":{},\"elementURL\":{}}}",
+        json_string(source_url),
+        json_string(message),
+        element_url.map(json_string).unwrap_or_else(|| "null".to_owned()),
+    )
+}
+
+/// Build the POST request carrying a serialized batch to an endpoint.
+pub(crate) fn build_delivery_request(endpoint: &ReportingEndpoint, body: Vec<u8>) -> RequestBuilder {
+    RequestBuilder::new(endpoint.url.clone(), Default::default())
+        .method(http::Method::POST)
+        .header(http::header::CONTENT_TYPE, "application/reports+json")
+        .body(Some(body))
+        .destination(Destination::Report)
+        .mode(RequestMode::CorsMode)
+}
+
+/// A report captured for delivery, carrying the fields the wire format needs.
+#[derive(Clone, Debug)]
+pub(crate) struct QueuedReport {
+    pub(crate) type_: String,
+    pub(crate) url: String,
+    pub(crate) endpoint: String,
+    pub(crate) body: Option<String>,
+    /// Monotonic timestamp (ms) the report was generated, for `age`/`max_age`.
+    pub(crate) generated_at: u64,
+    pub(crate) age_millis: u64,
+}
+
+impl QueuedReport {
+    /// Snapshot a live `Report` into an owned, deliverable form.
+    pub(crate) fn from_report(report: &Report, now: u64) -> QueuedReport {
+        QueuedReport {
+            type_: report.type_.to_string(),
+            url: report.url.to_string(),
+            // The destination group defaults to "default" per the spec.
+            endpoint: "default".to_owned(),
+            body: report.body.as_ref().map(|body| body.to_string()),
+            generated_at: now,
+            age_millis: 0,
+        }
+    }
+
+    fn expired(&self, now: u64, max_age: Duration) -> bool {
+        now.saturating_sub(self.generated_at) > max_age.as_millis() as u64
+    }
+}
+
+/// Group queued reports by destination endpoint, dropping expired ones, and cap the
+/// queue length. Returns one batch per endpoint group present in `endpoints`.
+pub(crate) fn group_reports<'a>(
+    reports: &mut Vec<QueuedReport>,
+    endpoints: &'a [ReportingEndpoint],
+    now: u64,
+) -> Vec<(&'a ReportingEndpoint, Vec<QueuedReport>)> {
+    // Drop stale reports and enforce the queue cap (oldest first).
+    reports.retain(|report| !report.expired(now, DEFAULT_MAX_AGE));
+    if reports.len() > MAX_QUEUED_REPORTS {
+        let overflow = reports.len() - MAX_QUEUED_REPORTS;
+        reports.drain(0..overflow);
+    }
+
+    let mut batches = Vec::new();
+    for endpoint in endpoints {
+        // Reserve backoff windows: a failing endpoint is temporarily skipped.
+        if endpoint.skip_until.is_some_and(|until| now < until) {
+            continue;
+        }
+        let group: Vec<QueuedReport> = reports
+            .iter()
+            .filter(|report| report.endpoint == endpoint.name)
+            // Never deliver a report to a cross-origin endpoint.
+            .filter(|report| {
+                ServoUrl::parse(&report.url)
+                    .map(|url| url.origin() == endpoint.url.origin())
+                    .unwrap_or(false)
+            })
+            .map(|report| QueuedReport {
+                age_millis: now.saturating_sub(report.generated_at),
+                ..report.clone()
+            })
+            .collect();
+        if !group.is_empty() {
+            batches.push((endpoint, group));
+        }
+    }
+    batches
+}
+
+/// Dispatch a delivery request through the resource threads. `on_response` is invoked
+/// with whether the fetch completed without a network-level error, off the script thread —
+/// the caller is responsible for hopping back onto a task source before touching any DOM
+/// state (e.g. an endpoint's `failures`/`skip_until` backoff fields).
+pub(crate) fn deliver(
+    resource_threads: &ResourceThreads,
+    request: RequestBuilder,
+    on_response: impl FnOnce(bool) + Send + 'static,
+) {
+    let (action_sender, action_receiver) = match ipc::channel() {
+        Ok(channel) => channel,
+        // Without a response channel the request cannot be sent; nothing is delivered.
+        Err(_) => return,
+    };
+    let mut on_response = Some(on_response);
+    ROUTER.add_route(
+        action_receiver.to_opaque(),
+        Box::new(move |message| {
+            // Only the end-of-response message says whether the fetch completed.
+            let Ok(FetchResponseMsg::ProcessResponseEOF(result)) = message.to::<FetchResponseMsg>()
+            else {
+                return;
+            };
+            if let Some(on_response) = on_response.take() {
+                on_response(result.is_ok());
+            }
+        }),
+    );
+    let _ = resource_threads.sender().send(CoreResourceMsg::Fetch(
+        request,
+        FetchChannels::ResponseMsg(action_sender, None),
+    ));
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
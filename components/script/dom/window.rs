@@ -92,6 +92,7 @@ use webrender_api::units::{DeviceIntSize, DevicePixel, LayoutPixel};
 
 use super::bindings::codegen::Bindings::MessagePortBinding::StructuredSerializeOptions;
 use super::bindings::trace::HashMapTracedValues;
+use crate::dom::bindings::callback::ExceptionHandling;
 use crate::dom::bindings::cell::{DomRefCell, Ref};
 use crate::dom::bindings::codegen::Bindings::DocumentBinding::{
     DocumentMethods, DocumentReadyState, NamedPropertyValue,
@@ -106,8 +107,8 @@ use crate::dom::bindings::codegen::Bindings::ReportingObserverBinding::Report;
 use crate::dom::bindings::codegen::Bindings::RequestBinding::RequestInit;
 use crate::dom::bindings::codegen::Bindings::VoidFunctionBinding::VoidFunction;
 use crate::dom::bindings::codegen::Bindings::WindowBinding::{
-    self, FrameRequestCallback, ScrollBehavior, ScrollToOptions, WindowMethods,
-    WindowPostMessageOptions,
+    self, FrameRequestCallback, IdleRequestCallback, IdleRequestOptions, ScrollBehavior,
+    ScrollToOptions, WindowMethods, WindowPostMessageOptions,
 };
 use crate::dom::bindings::codegen::UnionTypes::{RequestOrUSVString, StringOrFunction};
 use crate::dom::bindings::error::{Error, ErrorResult, Fallible};
@@ -123,7 +124,9 @@ use crate::dom::bindings::utils::GlobalStaticData;
 use crate::dom::bindings::weakref::DOMTracker;
 #[cfg(feature = "bluetooth")]
 use crate::dom::bluetooth::BluetoothExtraPermissionData;
+use crate::dom::accessibility::{AccessibilityEvent, AccessibilityNode, AccessibilityTree};
 use crate::dom::crypto::Crypto;
+use crate::dom::css_pixels::CSSPixels;
 use crate::dom::cssstyledeclaration::{CSSModificationAccess, CSSStyleDeclaration, CSSStyleOwner};
 use crate::dom::customelementregistry::CustomElementRegistry;
 use crate::dom::document::{AnimationFrameCallback, Document};
@@ -131,6 +134,7 @@ use crate::dom::element::Element;
 use crate::dom::event::{Event, EventBubbles, EventCancelable, EventStatus};
 use crate::dom::eventtarget::EventTarget;
 use crate::dom::gamepad::{Gamepad, contains_user_gesture};
+use crate::dom::gamepadregistry::GamepadRegistry;
 use crate::dom::gamepadevent::GamepadEventType;
 use crate::dom::globalscope::GlobalScope;
 use crate::dom::hashchangeevent::HashChangeEvent;
@@ -138,6 +142,7 @@ use crate::dom::history::History;
 use crate::dom::htmlcollection::{CollectionFilter, HTMLCollection};
 use crate::dom::htmliframeelement::HTMLIFrameElement;
 use crate::dom::idbfactory::IDBFactory;
+use crate::dom::idledeadline::IdleDeadline;
 use crate::dom::location::Location;
 use crate::dom::medialist::MediaList;
 use crate::dom::mediaquerylist::{MediaQueryList, MediaQueryListMatchState};
@@ -147,6 +152,10 @@ use crate::dom::navigator::Navigator;
 use crate::dom::node::{Node, NodeDamage, NodeTraits, from_untrusted_node_address};
 use crate::dom::performance::Performance;
 use crate::dom::promise::Promise;
+use crate::dom::reportingendpoint::{
+    build_delivery_request, css_parse_error_report_body, deliver, group_reports,
+    serialize_reports, QueuedReport, ReportingEndpoint,
+};
 use crate::dom::reportingobserver::ReportingObserver;
 use crate::dom::screen::Screen;
 use crate::dom::selection::Selection;
@@ -171,7 +180,7 @@ use crate::script_thread::ScriptThread;
 use crate::timers::{IsInterval, TimerCallback};
 use crate::unminify::unminified_path;
 use crate::webdriver_handlers::{find_node_by_unique_id_in_document, jsval_to_webdriver};
-use crate::{fetch, window_named_properties};
+use crate::{base64, fetch, window_named_properties};
 
 /// A callback to call when a response comes back from the `ImageCache`.
 ///
@@ -183,6 +192,24 @@ pub struct PendingImageCallback(
     Box<dyn Fn(PendingImageResponse) + 'static>,
 );
 
+/// A single registered `requestIdleCallback`, awaiting either idle time or its
+/// optional timeout.
+#[derive(JSTraceable, MallocSizeOf)]
+pub(crate) struct IdleRequest {
+    /// Monotonic handle returned to script, used by `cancelIdleCallback`.
+    handle: u32,
+    #[ignore_malloc_size_of = "Rc"]
+    callback: Rc<IdleRequestCallback>,
+    /// The instant the callback was registered, for timeout accounting.
+    #[ignore_malloc_size_of = "Instant"]
+    #[no_trace]
+    registered: Instant,
+    /// Optional timeout after which the callback must run with `didTimeout = true`.
+    #[ignore_malloc_size_of = "Duration"]
+    #[no_trace]
+    timeout: Option<Duration>,
+}
+
 /// Current state of the window object
 #[derive(Clone, Copy, Debug, JSTraceable, MallocSizeOf, PartialEq)]
 enum WindowState {
@@ -190,10 +217,100 @@ enum WindowState {
     Zombie, // Pipeline is closed, but the window hasn't been GCed yet.
 }
 
+/// How the embedder wants simple dialogs (`alert`/`confirm`/`prompt`) handled without
+/// round-tripping to the user. Drives WebDriver's [user prompt handler].
+///
+/// [user prompt handler]: https://w3c.github.io/webdriver/#dfn-user-prompt-handler
+#[derive(Clone, Copy, Debug, Default, JSTraceable, MallocSizeOf, PartialEq)]
+pub(crate) enum UserPromptHandler {
+    /// Show the dialog to the user and block on their response (the normal path).
+    #[default]
+    Default,
+    /// Dismiss the dialog: `confirm` → `false`, `prompt` → `null`.
+    Dismiss,
+    /// Accept the dialog: `confirm` → `true`, `prompt` → the supplied default.
+    Accept,
+    /// Like [`Dismiss`](Self::Dismiss), but the dialog is also recorded for automation.
+    DismissAndNotify,
+    /// Like [`Accept`](Self::Accept), but the dialog is also recorded for automation.
+    AcceptAndNotify,
+    /// Leave the dialog unhandled — return the default — but record it for automation.
+    Ignore,
+}
+
+impl UserPromptHandler {
+    /// Whether the dialog should short-circuit instead of going to the embedder.
+    fn short_circuits(&self) -> bool {
+        !matches!(self, Self::Default)
+    }
+
+    /// Whether a short-circuited dialog resolves as accepted.
+    fn accepts(&self) -> bool {
+        matches!(self, Self::Accept | Self::AcceptAndNotify)
+    }
+}
+
+/// The last simple dialog a non-default [`UserPromptHandler`] intercepted, retained so a
+/// WebDriver endpoint can read its text (and, for `prompt`, set the text before accepting).
+#[derive(Default, JSTraceable, MallocSizeOf)]
+pub(crate) struct LastDialog {
+    /// The dialog's message, as passed to `alert`/`confirm`/`prompt`.
+    pub(crate) message: String,
+    /// The kind of dialog (`"alert"`, `"confirm"`, or `"prompt"`).
+    pub(crate) kind: String,
+    /// For `prompt`, the default response text — overridable by automation before accept.
+    pub(crate) prompt_default: Option<String>,
+}
+
 /// How long we should wait before performing the initial reflow after `<body>` is parsed,
 /// assuming that `<body>` take this long to parse.
 const INITIAL_REFLOW_DELAY: Duration = Duration::from_millis(200);
 
+/// Duration of a `ScrollBehavior::Smooth` animation, per
+/// <https://drafts.csswg.org/cssom-view/#smooth-scroll>.
+const SMOOTH_SCROLL_DURATION: Duration = Duration::from_millis(250);
+
+/// Above this many tracked viewport-unit-dependent nodes, dirtying them individually on a
+/// viewport change is no cheaper than dirtying the whole document, so
+/// [`Window::restyle_viewport_unit_dependent_nodes`] falls back to a full restyle instead.
+const MAX_TRACKED_VIEWPORT_UNIT_DEPENDENT_NODES: usize = 4096;
+
+/// An in-flight `ScrollBehavior::Smooth` animation for a single scroll node, interpolating
+/// from the offset the node was at when the animation started toward its target offset.
+#[derive(JSTraceable, MallocSizeOf)]
+struct SmoothScrollAnimation {
+    /// The offset the node was at when this animation (re)started.
+    start: (f32, f32),
+    /// The offset this animation is easing toward.
+    target: (f32, f32),
+    /// When this animation (re)started, for computing how far along it is.
+    #[ignore_malloc_size_of = "Instant"]
+    #[no_trace]
+    start_time: Instant,
+}
+
+impl SmoothScrollAnimation {
+    /// The eased offset at `now`, or `None` once [`SMOOTH_SCROLL_DURATION`] has elapsed.
+    fn offset_at(&self, now: Instant) -> Option<(f32, f32)> {
+        let elapsed = now.saturating_duration_since(self.start_time);
+        if elapsed >= SMOOTH_SCROLL_DURATION {
+            return None;
+        }
+
+        // An ease-in-out (smoothstep) curve, matching other UAs' default smooth scroll timing.
+        let t = elapsed.as_secs_f32() / SMOOTH_SCROLL_DURATION.as_secs_f32();
+        let eased = t * t * (3.0 - 2.0 * t);
+        Some((
+            self.start.0 + (self.target.0 - self.start.0) * eased,
+            self.start.1 + (self.target.1 - self.start.1) * eased,
+        ))
+    }
+}
+
+/// The maximum length of an idle period, per `requestIdleCallback`: a callback is never told
+/// it has more than 50 ms of idle time available.
+const IDLE_PERIOD_CEILING: Duration = Duration::from_millis(50);
+
 /// During loading and parsing, layouts are suppressed to avoid flashing incomplete page
 /// contents.
 ///
@@ -354,6 +471,21 @@ pub(crate) struct Window {
     pending_images_for_rasterization:
         DomRefCell<HashMapTracedValues<PendingImageRasterizationKey, Vec<Dom<Node>>>>,
 
+    /// Nodes whose most recently computed style read a viewport-relative unit (`vw`, `vh`,
+    /// `vmin`, `vmax`, `vi`, `vb`, `svh`, `lvh`, `dvh`, …), as reported by layout after each
+    /// reflow. Consulted by [`Self::restyle_viewport_unit_dependent_nodes`] so that a viewport
+    /// change only restyles the nodes that actually depend on it, instead of the whole
+    /// document.
+    viewport_unit_dependent_nodes: DomRefCell<Vec<Dom<Node>>>,
+
+    /// `HTMLImageElement.decode()` promises awaiting rasterization, keyed the same way as
+    /// [`Self::pending_images_for_rasterization`]. Resolved when the matching
+    /// `RasterizationCompleteResponse` (or, for a zero-size decode, a fully-available
+    /// `PendingImageResponse`) arrives, and rejected if the element's `src` changes first.
+    #[ignore_malloc_size_of = "Rc"]
+    pending_image_decode_promises:
+        DomRefCell<HashMapTracedValues<PendingImageRasterizationKey, Vec<Rc<Promise>>>>,
+
     /// Directory to store unminified css for this window if unminify-css
     /// opt is enabled.
     unminified_css_dir: DomRefCell<Option<String>>,
@@ -392,11 +524,11 @@ pub(crate) struct Window {
 
     throttled: Cell<bool>,
 
-    /// A shared marker for the validity of any cached layout values. A value of true
-    /// indicates that any such values remain valid; any new layout that invalidates
-    /// those values will cause the marker to be set to false.
+    /// Per-[`LayoutValueCategory`] version counters backing [`Window::cache_layout_value`]. A
+    /// cached [`LayoutValue`] remains valid only as long as none of the categories it read
+    /// from have had their counter bumped since, via [`Window::invalidate_layout_value_categories`].
     #[ignore_malloc_size_of = "Rc is hard"]
-    layout_marker: DomRefCell<Rc<Cell<bool>>>,
+    layout_value_versions: DomRefCell<HashMap<LayoutValueCategory, Rc<Cell<u64>>>>,
 
     /// <https://dom.spec.whatwg.org/#window-current-event>
     current_event: DomRefCell<Option<Dom<Event>>>,
@@ -406,6 +538,41 @@ pub(crate) struct Window {
 
     /// <https://w3c.github.io/reporting/#windoworworkerglobalscope-reports>
     report_list: DomRefCell<Vec<Report>>,
+
+    /// Named endpoints parsed from the `Reporting-Endpoints` response header, used
+    /// to deliver queued reports off-process.
+    reporting_endpoints: DomRefCell<Vec<ReportingEndpoint>>,
+
+    /// Lazily-built accessibility tree, rebuilt only after an explicit
+    /// [`Window::invalidate_accessibility_tree`]; no DOM mutation path calls it.
+    a11y_tree: AccessibilityTree,
+
+    /// Registered `requestIdleCallback` callbacks awaiting execution. Handles are drawn from
+    /// the `Document`'s animation-frame counter, so this list is keyed by `IdleRequest::handle`
+    /// rather than owning a counter of its own.
+    idle_request_callbacks: DomRefCell<Vec<IdleRequest>>,
+
+    /// This window's map from physical gamepad (GilRs device index) to slot, so
+    /// connect/disconnect/update events resolve to one `Gamepad` per device. Each `Window`
+    /// creates its own registry; it is not shared with other windows.
+    #[ignore_malloc_size_of = "Rc"]
+    #[no_trace]
+    gamepad_registry: Rc<RefCell<GamepadRegistry>>,
+
+    /// Automation's simple-dialog handling mode; [`UserPromptHandler::Default`] shows
+    /// dialogs to the user, any other mode short-circuits them.
+    user_prompt_handler: Cell<UserPromptHandler>,
+
+    /// The last dialog intercepted by a non-default [`user_prompt_handler`], exposed to
+    /// the WebDriver layer.
+    ///
+    /// [`user_prompt_handler`]: Self::user_prompt_handler
+    last_dialog: DomRefCell<LastDialog>,
+
+    /// In-flight `ScrollBehavior::Smooth` animations, keyed by the target scroll node, driven
+    /// by [`Window::tick_smooth_scroll_animations`] on each "update the rendering" reflow.
+    #[no_trace]
+    smooth_scroll_animations: DomRefCell<HashMap<ExternalScrollId, SmoothScrollAnimation>>,
 }
 
 impl Window {
@@ -535,10 +702,158 @@ impl Window {
         self.report_list.borrow_mut().push(report);
     }
 
+    /// Build and record a `"css-parse-error"` report for a stylesheet parse error, then
+    /// deliver it to every registered [`ReportingObserver`] whose type filter accepts it —
+    /// including one created with `{buffered: true}` that's observing for the first time,
+    /// the same as for other report types. Also appended to [`Self::report_list`] so a later
+    /// call to [`Self::deliver_buffered_reports`] still ships it to any configured
+    /// `Reporting-Endpoints` collector.
+    ///
+    /// Called from the script thread's handler for `ScriptThreadMessage::ReportCSSError`: the
+    /// error itself is detected on the style thread by [`CSSErrorReporter`], which only holds
+    /// an IPC channel back to script and has no `Window` to report through directly.
+    pub(crate) fn report_css_parse_error(
+        &self,
+        stylesheet_url: ServoUrl,
+        line: u32,
+        column: u32,
+        message: String,
+        element_url: Option<ServoUrl>,
+        can_gc: CanGc,
+    ) {
+        let body = css_parse_error_report_body(
+            stylesheet_url.as_str(),
+            line,
+            column,
+            &message,
+            element_url.as_ref().map(ServoUrl::as_str),
+        );
+        let report = Report {
+            type_: DOMString::from("css-parse-error"),
+            url: USVString(stylesheet_url.into_string()),
+            body: Some(DOMString::from(body)),
+        };
+        self.append_report(report.clone());
+
+        for observer in self.registered_reporting_observers() {
+            observer.queue_report_if_matching(&report, can_gc);
+        }
+    }
+
     pub(crate) fn buffered_reports(&self) -> Vec<Report> {
         self.report_list.borrow().clone()
     }
 
+    /// Record the endpoints declared by a document's `Reporting-Endpoints` header.
+    pub(crate) fn set_reporting_endpoints(&self, endpoints: Vec<ReportingEndpoint>) {
+        *self.reporting_endpoints.borrow_mut() = endpoints;
+    }
+
+    /// Deliver the buffered reports to their configured endpoints, grouped per
+    /// endpoint and POSTed as `application/reports+json` batches. Cross-origin,
+    /// expired, and over-cap reports are dropped; failing endpoints are skipped.
+    pub(crate) fn deliver_buffered_reports(&self) {
+        let endpoints = self.reporting_endpoints.borrow().clone();
+        if endpoints.is_empty() {
+            return;
+        }
+        let now = self.as_global_scope().monotonic_now_ms();
+        let user_agent = self.as_global_scope().get_user_agent().to_string();
+        let mut queued: Vec<QueuedReport> = self
+            .report_list
+            .borrow()
+            .iter()
+            .map(|report| QueuedReport::from_report(report, now))
+            .collect();
+        let resource_threads = self.as_global_scope().resource_threads().clone();
+        for (endpoint, batch) in group_reports(&mut queued, &endpoints, now) {
+            let body = serialize_reports(&batch, &user_agent);
+            let request = build_delivery_request(endpoint, body);
+            let endpoint_url = endpoint.url.clone();
+            let this = Trusted::new(self);
+            let task_source = self
+                .upcast::<GlobalScope>()
+                .task_manager()
+                .networking_task_source();
+            deliver(&resource_threads, request, move |success| {
+                task_source.queue(task!(reporting_endpoint_delivered: move || {
+                    let window = this.root();
+                    window.record_delivery_result(&endpoint_url, success);
+                }));
+            });
+        }
+    }
+
+    /// Update an endpoint's failure counter and backoff window after an attempted
+    /// delivery, matching it by URL since a group `name` may be shared by several
+    /// failover endpoints.
+    fn record_delivery_result(&self, endpoint_url: &ServoUrl, success: bool) {
+        let mut endpoints = self.reporting_endpoints.borrow_mut();
+        let Some(endpoint) = endpoints
+            .iter_mut()
+            .find(|endpoint| &endpoint.url == endpoint_url)
+        else {
+            return;
+        };
+        if success {
+            endpoint.failures = 0;
+            endpoint.skip_until = None;
+        } else {
+            endpoint.failures = endpoint.failures.saturating_add(1);
+            let now = self.as_global_scope().monotonic_now_ms();
+            endpoint.skip_until = Some(now + endpoint.backoff().as_millis() as u64);
+        }
+    }
+
+    /// Emit an accessibility lifecycle event to the embedder / assistive tech.
+    pub(crate) fn emit_accessibility_event(&self, event: AccessibilityEvent) {
+        self.send_to_embedder(EmbedderMsg::AccessibilityEvent(self.webview_id(), event));
+    }
+
+    /// Mark the accessibility tree stale so the next query rebuilds it.
+    pub(crate) fn invalidate_accessibility_tree(&self) {
+        self.a11y_tree.invalidate();
+    }
+
+    /// Query a subtree of the accessibility tree by node id, for the embedder.
+    pub(crate) fn accessibility_subtree(&self, node_id: u64) -> Option<AccessibilityNode> {
+        self.a11y_tree.subtree(self, node_id)
+    }
+
+    /// Set the automation user-prompt handler. A non-default handler makes
+    /// `alert`/`confirm`/`prompt` short-circuit instead of blocking on the embedder.
+    pub(crate) fn set_user_prompt_handler(&self, handler: UserPromptHandler) {
+        self.user_prompt_handler.set(handler);
+    }
+
+    /// The message of the last dialog intercepted by a non-default handler, if any.
+    pub(crate) fn last_dialog_message(&self) -> Option<String> {
+        let last = self.last_dialog.borrow();
+        (!last.kind.is_empty()).then(|| last.message.clone())
+    }
+
+    /// The pending `prompt` default text, for WebDriver to read before accepting.
+    pub(crate) fn last_prompt_default(&self) -> Option<String> {
+        self.last_dialog.borrow().prompt_default.clone()
+    }
+
+    /// Replace the recorded `prompt` default text. A short-circuited `prompt` records its own
+    /// default and resolves with it, so text set here does not change what `accept` returns.
+    pub(crate) fn set_prompt_text(&self, text: String) {
+        self.last_dialog.borrow_mut().prompt_default = Some(text);
+    }
+
+    /// Record the last short-circuited dialog so automation can inspect it. The `notify`
+    /// modes and `ignore` keep the record; the plain `dismiss`/`accept` modes also record
+    /// it, since a WebDriver endpoint may query it regardless.
+    fn capture_dialog(&self, kind: &str, message: &str, prompt_default: Option<String>) {
+        *self.last_dialog.borrow_mut() = LastDialog {
+            message: message.to_owned(),
+            kind: kind.to_owned(),
+            prompt_default,
+        };
+    }
+
     /// Returns the window proxy if it has not been discarded.
     /// <https://html.spec.whatwg.org/multipage/#a-browsing-context-is-discarded>
     pub(crate) fn undiscarded_window_proxy(&self) -> Option<DomRoot<WindowProxy>> {
@@ -624,6 +939,7 @@ impl Window {
     pub(crate) fn handle_image_rasterization_complete_notification(
         &self,
         response: RasterizationCompleteResponse,
+        can_gc: CanGc,
     ) {
         let mut images = self.pending_images_for_rasterization.borrow_mut();
         let nodes = images.entry((response.image_id, response.requested_size));
@@ -635,9 +951,62 @@ impl Window {
             node.dirty(NodeDamage::Other);
         }
         nodes.remove();
+
+        // A completed rasterization fulfils any `decode()` promises waiting on this key.
+        self.resolve_image_decode_promises(
+            (response.image_id, response.requested_size),
+            can_gc,
+        );
+    }
+
+    /// Register an `HTMLImageElement.decode()` promise to be resolved when the image's
+    /// current request rasterizes at `key`'s size (or, for a zero-size key, when its bytes
+    /// become fully available via [`Self::pending_image_notification`]).
+    pub(crate) fn register_image_decode_promise(
+        &self,
+        key: PendingImageRasterizationKey,
+        promise: Rc<Promise>,
+    ) {
+        self.pending_image_decode_promises
+            .borrow_mut()
+            .entry(key)
+            .or_default()
+            .push(promise);
+    }
+
+    /// Resolve every `decode()` promise registered for `key`. Uses the take-then-restore
+    /// borrow pattern of [`Self::pending_image_notification`] so a promise callback that
+    /// triggers GC cannot re-borrow the map.
+    fn resolve_image_decode_promises(&self, key: PendingImageRasterizationKey, can_gc: CanGc) {
+        // Detach this key's promises before settling them: a resolution callback can trigger
+        // GC, which must not find the map mutably borrowed.
+        let for_key = self
+            .pending_image_decode_promises
+            .borrow_mut()
+            .remove(&key);
+        for promise in for_key.into_iter().flatten() {
+            promise.resolve_native(&(), can_gc);
+        }
+    }
+
+    /// Reject every `decode()` promise registered for `key` with an `EncodingError`. Called
+    /// when the element's current request is `None`/failed, or its `src` changes before
+    /// rasterization completes.
+    pub(crate) fn reject_image_decode_promises(
+        &self,
+        key: PendingImageRasterizationKey,
+        can_gc: CanGc,
+    ) {
+        let for_key = self
+            .pending_image_decode_promises
+            .borrow_mut()
+            .remove(&key);
+        for promise in for_key.into_iter().flatten() {
+            promise.reject_error(Error::Encoding, can_gc);
+        }
     }
 
-    pub(crate) fn pending_image_notification(&self, response: PendingImageResponse) {
+    pub(crate) fn pending_image_notification(&self, response: PendingImageResponse, can_gc: CanGc) {
         // We take the images here, in order to prevent maintaining a mutable borrow when
         // image callbacks are called. These, in turn, can trigger garbage collection.
         // Normally this shouldn't trigger more pending image notifications, but just in
@@ -645,6 +1014,8 @@ impl Window {
         let mut images = std::mem::take(&mut *self.pending_image_callbacks.borrow_mut());
         let Entry::Occupied(callbacks) = images.entry(response.id) else {
             let _ = std::mem::replace(&mut *self.pending_image_callbacks.borrow_mut(), images);
+            // A zero-size `decode()` may still be waiting even with no layout callbacks.
+            self.notify_image_decode_availability(&response, can_gc);
             return;
         };
 
@@ -662,6 +1033,20 @@ impl Window {
         }
 
         let _ = std::mem::replace(&mut *self.pending_image_callbacks.borrow_mut(), images);
+        self.notify_image_decode_availability(&response, can_gc);
+    }
+
+    /// Settle any zero-size `decode()` promise once the image's bytes are fully available:
+    /// `Loaded`/`PlaceholderLoaded` resolves it, a `None` response rejects it.
+    fn notify_image_decode_availability(&self, response: &PendingImageResponse, can_gc: CanGc) {
+        let key = (response.id, DeviceIntSize::zero());
+        match response.response {
+            ImageResponse::Loaded(_, _) | ImageResponse::PlaceholderLoaded(_, _) => {
+                self.resolve_image_decode_promises(key, can_gc);
+            },
+            ImageResponse::None => self.reject_image_decode_promises(key, can_gc),
+            ImageResponse::MetadataLoaded(_) => {},
+        }
     }
 
     pub(crate) fn compositor_api(&self) -> &CrossProcessCompositorApi {
@@ -712,10 +1097,9 @@ impl Window {
     /// <https://www.w3.org/TR/gamepad/#dfn-gamepadconnected>
     fn handle_gamepad_connect(
         &self,
-        // As the spec actually defines how to set the gamepad index, the GilRs index
-        // is currently unused, though in practice it will almost always be the same.
-        // More infra is currently needed to track gamepads across windows.
-        _index: usize,
+        // The GilRs device index keys the shared registry, so every window that receives
+        // this connect event derives the same canonical slot for the physical device.
+        device_index: usize,
         name: String,
         axis_bounds: (f64, f64),
         button_bounds: (f64, f64),
@@ -731,10 +1115,12 @@ impl Window {
                 let window = this.root();
 
                 let navigator = window.Navigator();
-                let selected_index = navigator.select_gamepad_index();
+                // Take the slot from this window's registry, which returns the existing slot
+                // for a device it already knows.
+                let selected_index = window.gamepad_registry.borrow_mut().connect(device_index);
                 let gamepad = Gamepad::new(
                     &window,
-                    selected_index,
+                    selected_index as u32,
                     name,
                     "standard".into(),
                     axis_bounds,
@@ -756,17 +1142,20 @@ impl Window {
             .queue(task!(gamepad_disconnected: move || {
                 let window = this.root();
                 let navigator = window.Navigator();
-                if let Some(gamepad) = navigator.get_gamepad(index) {
+                let Some(slot) = window.gamepad_registry.borrow_mut().disconnect(index) else {
+                    return;
+                };
+                if let Some(gamepad) = navigator.get_gamepad(slot) {
                     if window.Document().is_fully_active() {
                         gamepad.update_connected(false, gamepad.exposed(), CanGc::note());
-                        navigator.remove_gamepad(index);
+                        navigator.remove_gamepad(slot);
                     }
                 }
             }));
     }
 
     /// <https://www.w3.org/TR/gamepad/#receiving-inputs>
-    fn receive_new_gamepad_button_or_axis(&self, index: usize, update_type: GamepadUpdateType) {
+    fn receive_new_gamepad_button_or_axis(&self, device_index: usize, update_type: GamepadUpdateType) {
         let this = Trusted::new(self);
 
         // <https://w3c.github.io/gamepad/#dfn-update-gamepad-state>
@@ -774,7 +1163,11 @@ impl Window {
                 task!(update_gamepad_state: move || {
                     let window = this.root();
                     let navigator = window.Navigator();
-                    if let Some(gamepad) = navigator.get_gamepad(index) {
+                    // Resolve the physical device to the slot this window's registry gave it.
+                    let Some(slot) = window.gamepad_registry.borrow().slot(device_index) else {
+                        return;
+                    };
+                    if let Some(gamepad) = navigator.get_gamepad(slot) {
                         let current_time = window.Performance().Now();
                         gamepad.update_timestamp(*current_time);
                         match update_type {
@@ -785,7 +1178,15 @@ impl Window {
                                 gamepad.map_and_normalize_buttons(index, value);
                             }
                         };
-                        if !navigator.has_gamepad_gesture() && contains_user_gesture(update_type) {
+                        // Only expose the device here once per document, on the first user
+                        // gesture; the registry records the pipelines it was exposed to.
+                        let first_exposure = !navigator.has_gamepad_gesture() &&
+                            contains_user_gesture(update_type) &&
+                            window
+                                .gamepad_registry
+                                .borrow_mut()
+                                .expose(device_index, window.pipeline_id());
+                        if first_exposure {
                             navigator.set_has_gamepad_gesture(true);
                             navigator.GetGamepads()
                                 .iter()
@@ -826,10 +1227,7 @@ pub(crate) fn base64_btoa(input: DOMString) -> Fallible<DOMString> {
 
         // "and then must apply the base64 algorithm to that sequence of
         //  octets, and return the result. [RFC4648]"
-        let config =
-            base64::engine::general_purpose::GeneralPurposeConfig::new().with_encode_padding(true);
-        let engine = base64::engine::GeneralPurpose::new(&base64::alphabet::STANDARD, config);
-        Ok(DOMString::from(engine.encode(octets)))
+        Ok(DOMString::from(base64::encode_standard(&octets)))
     }
 }
 
@@ -876,12 +1274,7 @@ pub(crate) fn base64_atob(input: DOMString) -> Fallible<DOMString> {
         return Err(Error::InvalidCharacter);
     }
 
-    let config = base64::engine::general_purpose::GeneralPurposeConfig::new()
-        .with_decode_padding_mode(base64::engine::DecodePaddingMode::RequireNone)
-        .with_decode_allow_trailing_bits(true);
-    let engine = base64::engine::GeneralPurpose::new(&base64::alphabet::STANDARD, config);
-
-    let data = engine.decode(input).map_err(|_| Error::InvalidCharacter)?;
+    let data = base64::decode_forgiving(input).ok_or(Error::InvalidCharacter)?;
     Ok(data.iter().map(|&b| b as char).collect::<String>().into())
 }
 
@@ -905,6 +1298,12 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
             stdout.flush().unwrap();
             stderr.flush().unwrap();
         }
+        self.emit_accessibility_event(AccessibilityEvent::Alert(s.to_string()));
+        // Under automation, an alert has nothing to resolve: record it and return.
+        if self.user_prompt_handler.get().short_circuits() {
+            self.capture_dialog("alert", &s, None);
+            return;
+        }
         let (sender, receiver) =
             ProfiledIpc::channel(self.global().time_profiler_chan().clone()).unwrap();
         let dialog = SimpleDialog::Alert {
@@ -918,6 +1317,11 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
 
     // https://html.spec.whatwg.org/multipage/#dom-confirm
     fn Confirm(&self, s: DOMString) -> bool {
+        let handler = self.user_prompt_handler.get();
+        if handler.short_circuits() {
+            self.capture_dialog("confirm", &s, None);
+            return handler.accepts();
+        }
         let (sender, receiver) =
             ProfiledIpc::channel(self.global().time_profiler_chan().clone()).unwrap();
         let dialog = SimpleDialog::Confirm {
@@ -931,6 +1335,21 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
 
     // https://html.spec.whatwg.org/multipage/#dom-prompt
     fn Prompt(&self, message: DOMString, default: DOMString) -> Option<DOMString> {
+        let handler = self.user_prompt_handler.get();
+        if handler.short_circuits() {
+            self.capture_dialog("prompt", &message, Some(default.to_string()));
+            if !handler.accepts() {
+                return None;
+            }
+            // Accepting resolves with the default text `capture_dialog` has just recorded.
+            let text = self
+                .last_dialog
+                .borrow()
+                .prompt_default
+                .clone()
+                .unwrap_or_default();
+            return Some(text.into());
+        }
         let (sender, receiver) =
             ProfiledIpc::channel(self.global().time_profiler_chan().clone()).unwrap();
         let dialog = SimpleDialog::Prompt {
@@ -1207,6 +1626,32 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
         self.as_global_scope().clear_timeout_or_interval(handle);
     }
 
+    // https://w3c.github.io/requestidlecallback/#dom-window-requestidlecallback
+    fn RequestIdleCallback(
+        &self,
+        callback: Rc<IdleRequestCallback>,
+        options: &IdleRequestOptions,
+    ) -> u32 {
+        // Draw from the same handle counter as `requestAnimationFrame` so that idle-callback
+        // and animation-frame handles share one namespace and are never reused across the two.
+        let handle = self.Document().next_animation_frame_ident();
+        let timeout = (options.timeout > 0).then(|| Duration::from_millis(options.timeout as u64));
+        self.idle_request_callbacks.borrow_mut().push(IdleRequest {
+            handle,
+            callback,
+            registered: Instant::now(),
+            timeout,
+        });
+        handle
+    }
+
+    // https://w3c.github.io/requestidlecallback/#dom-window-cancelidlecallback
+    fn CancelIdleCallback(&self, handle: u32) {
+        self.idle_request_callbacks
+            .borrow_mut()
+            .retain(|request| request.handle != handle);
+    }
+
     // https://html.spec.whatwg.org/multipage/#dom-windowtimers-setinterval
     fn SetInterval(
         &self,
@@ -1559,27 +2004,22 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
     // https://drafts.csswg.org/cssom-view/#dom-window-innerheight
     //TODO Include Scrollbar
     fn InnerHeight(&self) -> i32 {
-        self.viewport_details
-            .get()
-            .size
-            .height
-            .to_i32()
-            .unwrap_or(0)
+        CSSPixels::from_px(self.viewport_details.get().size.height).round()
     }
 
     // https://drafts.csswg.org/cssom-view/#dom-window-innerwidth
     //TODO Include Scrollbar
     fn InnerWidth(&self) -> i32 {
-        self.viewport_details.get().size.width.to_i32().unwrap_or(0)
+        CSSPixels::from_px(self.viewport_details.get().size.width).round()
     }
 
     /// <https://drafts.csswg.org/cssom-view/#dom-window-scrollx>
     fn ScrollX(&self) -> i32 {
-        self.scroll_offset_query_with_external_scroll_id(
+        let offset = self.scroll_offset_query_with_external_scroll_id(
             self.pipeline_id().root_scroll_id(),
             CanGc::note(),
-        )
-        .x as i32
+        );
+        CSSPixels::from_px(offset.x).round()
     }
 
     // https://drafts.csswg.org/cssom-view/#dom-window-pagexoffset
@@ -1589,11 +2029,11 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
 
     /// <https://drafts.csswg.org/cssom-view/#dom-window-scrolly>
     fn ScrollY(&self) -> i32 {
-        self.scroll_offset_query_with_external_scroll_id(
+        let offset = self.scroll_offset_query_with_external_scroll_id(
             self.pipeline_id().root_scroll_id(),
             CanGc::note(),
-        )
-        .y as i32
+        );
+        CSSPixels::from_px(offset.y).round()
     }
 
     // https://drafts.csswg.org/cssom-view/#dom-window-pageyoffset
@@ -1705,17 +2145,20 @@ impl WindowMethods<crate::DomTypeHolder> for Window {
     // https://drafts.csswg.org/cssom-view/#dom-window-outerheight
     fn OuterHeight(&self) -> i32 {
         let (size, _) = self.client_window();
-        size.height.to_i32().unwrap_or(1)
+        CSSPixels::from_px(size.height as f32).round()
     }
 
     // https://drafts.csswg.org/cssom-view/#dom-window-outerwidth
     fn OuterWidth(&self) -> i32 {
         let (size, _) = self.client_window();
-        size.width.to_i32().unwrap_or(1)
+        CSSPixels::from_px(size.width as f32).round()
     }
 
     // https://drafts.csswg.org/cssom-view/#dom-window-devicepixelratio
     fn DevicePixelRatio(&self) -> Finite<f64> {
+        // `devicePixelRatio` is a unitless scale factor, not a pixel length, so
+        // it must not be quantized through `CSSPixels`' 1/64px fixed-point
+        // representation.
         Finite::wrap(self.device_pixel_ratio().get() as f64)
     }
 
@@ -2005,6 +2448,16 @@ impl Window {
         transfer: CustomAutoRooterGuard<Vec<*mut JSObject>>,
     ) -> ErrorResult {
         // Step 1-2, 6-8.
+        //
+        // Per <https://html.spec.whatwg.org/multipage/#structuredserializewithtransfer>: "If
+        // transferables contains more than one occurrence of the same object, then throw a
+        // 'DataCloneError'". Checked here by pointer identity, before any transferred
+        // `ArrayBuffer`'s backing store is moved and detached by `structuredclone::write`, so
+        // a duplicate can't cause the same buffer to be detached twice.
+        let mut seen_for_transfer = HashSet::with_capacity(transfer.len());
+        if !transfer.iter().all(|object| seen_for_transfer.insert(*object)) {
+            return Err(Error::DataClone);
+        }
         let data = structuredclone::write(cx, message, Some(transfer))?;
 
         // Step 3-5.
@@ -2086,8 +2539,9 @@ impl Window {
             .min(scrolling_area.height() as f64 - viewport.height as f64)
             .max(0.0f64);
 
-        // Step 10
-        //TODO handling ongoing smooth scrolling
+        // Step 10: if the current position is already at (x, y), bail out. This also covers
+        // retargeting an ongoing smooth scroll: `perform_a_scroll` below reuses the existing
+        // animation for this node rather than starting a second one when it isn't already done.
         if x == self.ScrollX() as f64 && y == self.ScrollY() as f64 {
             return;
         }
@@ -2113,19 +2567,73 @@ impl Window {
         x: f32,
         y: f32,
         scroll_id: ExternalScrollId,
-        _behavior: ScrollBehavior,
+        behavior: ScrollBehavior,
         _element: Option<&Element>,
         can_gc: CanGc,
     ) {
         // TODO Step 1
-        // TODO(mrobinson, #18709): Add smooth scrolling support to WebRender so that we can
-        // properly process ScrollBehavior here.
+        // TODO: `behavior` should resolve `Auto` against the `scroll-behavior` computed value
+        // of the associated element; we only animate an explicit `Smooth`.
+        if behavior == ScrollBehavior::Smooth {
+            let start = self.scroll_offset_query_with_external_scroll_id(scroll_id, can_gc);
+            self.smooth_scroll_animations.borrow_mut().insert(
+                scroll_id,
+                SmoothScrollAnimation {
+                    start: (start.x, start.y),
+                    target: (x, y),
+                    start_time: Instant::now(),
+                },
+            );
+            return;
+        }
+
+        // An instantaneous scroll of a node overrides any smooth scroll already in flight for it.
+        self.smooth_scroll_animations.borrow_mut().remove(&scroll_id);
         self.reflow(
             ReflowGoal::UpdateScrollNode(scroll_id, Vector2D::new(x, y)),
             can_gc,
         );
     }
 
+    /// Advances every in-flight [`SmoothScrollAnimation`] toward its target and applies the
+    /// resulting offset, so that `ScrollX`/`ScrollY` (and the equivalent element scroll
+    /// queries) reflect the intermediate position. Called once per "update the rendering"
+    /// reflow; finished animations are dropped after their exact target is applied.
+    fn tick_smooth_scroll_animations(&self, can_gc: CanGc) {
+        if self.smooth_scroll_animations.borrow().is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut finished = Vec::new();
+        let offsets: Vec<(ExternalScrollId, f32, f32)> = self
+            .smooth_scroll_animations
+            .borrow()
+            .iter()
+            .map(|(scroll_id, animation)| match animation.offset_at(now) {
+                Some((x, y)) => (*scroll_id, x, y),
+                None => {
+                    finished.push(*scroll_id);
+                    (*scroll_id, animation.target.0, animation.target.1)
+                },
+            })
+            .collect();
+
+        if !finished.is_empty() {
+            let mut animations = self.smooth_scroll_animations.borrow_mut();
+            for scroll_id in finished {
+                animations.remove(&scroll_id);
+            }
+        }
+
+        for (scroll_id, x, y) in offsets {
+            self.reflow(
+                ReflowGoal::UpdateScrollNode(scroll_id, Vector2D::new(x, y)),
+                can_gc,
+            );
+        }
+    }
+
     pub(crate) fn device_pixel_ratio(&self) -> Scale<f32, CSSPixel, DevicePixel> {
         self.viewport_details.get().hidpi_scale_factor
     }
@@ -2178,20 +2686,34 @@ impl Window {
         }
 
         debug!("script: performing reflow for goal {reflow_goal:?}");
+        // Name the marker after the originating query, when there is one, so devtools can
+        // tell a forced-synchronous-layout stall caused by a script query (e.g.
+        // `getComputedStyle`, `getBoundingClientRect`) apart from an ordinary paint-driven
+        // reflow.
         let marker = if self.need_emit_timeline_marker(TimelineMarkerType::Reflow) {
-            Some(TimelineMarker::start("Reflow".to_owned()))
+            let marker_name = match reflow_goal {
+                ReflowGoal::LayoutQuery(query_msg) => format!("Reflow (query: {query_msg:?})"),
+                _ => "Reflow".to_owned(),
+            };
+            Some(TimelineMarker::start(marker_name))
         } else {
             None
         };
 
+        if let ReflowGoal::UpdateScrollNode(..) = reflow_goal {
+            // A pure scroll doesn't restyle anything, so it only needs to invalidate cached
+            // layout values that actually depend on the scroll offset (e.g. a cached
+            // `getBoundingClientRect`), not the whole cache.
+            self.invalidate_layout_value_categories(&[LayoutValueCategory::ScrollOffset]);
+        }
+
         let restyle_reason = document.restyle_reason();
         document.clear_restyle_reasons();
         let restyle = if restyle_reason.needs_restyle() {
             debug!("Invalidating layout cache due to reflow condition {restyle_reason:?}",);
-            // Invalidate any existing cached layout values.
-            self.layout_marker.borrow().set(false);
-            // Create a new layout caching token.
-            *self.layout_marker.borrow_mut() = Rc::new(Cell::new(true));
+            // A restyle may have rebuilt arbitrary parts of the box tree; invalidate any cached
+            // layout value that depends on it.
+            self.invalidate_layout_value_categories(&[LayoutValueCategory::BoxTreeGeneration]);
 
             let stylesheets_changed = document.flush_stylesheets_for_reflow();
             let pending_restyles = document.drain_pending_restyles();
@@ -2238,6 +2760,14 @@ impl Window {
             results.pending_images,
             results.pending_rasterization_images,
         );
+        #[allow(unsafe_code)]
+        {
+            *self.viewport_unit_dependent_nodes.borrow_mut() = results
+                .viewport_unit_dependent_nodes
+                .into_iter()
+                .map(|node| Dom::from_ref(&*unsafe { from_untrusted_node_address(node) }))
+                .collect();
+        }
         document
             .iframes_mut()
             .handle_new_iframe_sizes_after_layout(self, results.iframe_sizes);
@@ -2265,8 +2795,17 @@ impl Window {
         self.Document().ensure_safe_to_run_script_or_layout();
 
         let updating_the_rendering = reflow_goal == ReflowGoal::UpdateTheRendering;
+        if updating_the_rendering {
+            self.tick_smooth_scroll_animations(can_gc);
+        }
         let issued_reflow = self.force_reflow(reflow_goal);
 
+        // Per the event loop's "update the rendering" step, an idle period may start once
+        // rendering has been brought up to date for this turn.
+        if updating_the_rendering {
+            self.run_idle_callbacks(can_gc);
+        }
+
         let document = self.Document();
         let font_face_set = document.Fonts(can_gc);
         let is_ready_state_complete = document.ReadyState() == DocumentReadyState::Complete;
@@ -2367,6 +2906,9 @@ impl Window {
         self.layout_blocker
             .set(LayoutBlocker::FiredLoadEventOrParsingTimerExpired);
 
+        // The document has finished loading; surface it to assistive technology.
+        self.emit_accessibility_event(AccessibilityEvent::DocumentLoadComplete);
+
         // We do this immediately instead of scheduling a future task, because this can
         // happen if parsing is taking a very long time, which means that the
         // `ScriptThread` is busy doing the parsing and not doing layouts.
@@ -2381,6 +2923,58 @@ impl Window {
         self.Document().update_the_rendering(can_gc);
     }
 
+    /// Drain the `requestIdleCallback` queue at the end of an event-loop turn, after tasks
+    /// and the update-the-rendering step. Callbacks whose `timeout` has elapsed run
+    /// unconditionally with `didTimeout = true`; the rest run only while idle time remains
+    /// before `deadline`. Callbacks left unserviced stay queued for a later turn.
+    /// <https://w3c.github.io/requestidlecallback/#start-an-idle-period-algorithm>
+    pub(crate) fn run_idle_callbacks(&self, can_gc: CanGc) {
+        let now = Instant::now();
+
+        // The idle deadline is the remaining frame budget, clamped to the 50 ms ceiling. When
+        // an animation frame is pending its estimated start bounds the period, so idle work
+        // never eats into the next frame.
+        let mut budget = IDLE_PERIOD_CEILING;
+        if let Some(until_next_frame) = self.Document().time_until_next_animation_frame(now) {
+            budget = budget.min(until_next_frame);
+        }
+        let deadline = now + budget;
+
+        // Take the queue so callbacks registered during invocation accumulate separately and
+        // a reentrant drain cannot double-borrow — mirroring the image-callback path.
+        let pending = std::mem::take(&mut *self.idle_request_callbacks.borrow_mut());
+        let (timed_out, mut idle): (Vec<IdleRequest>, Vec<IdleRequest>) =
+            pending.into_iter().partition(|request| {
+                request
+                    .timeout
+                    .is_some_and(|timeout| now.saturating_duration_since(request.registered) >= timeout)
+            });
+
+        for request in timed_out {
+            let idle_deadline = IdleDeadline::new(self.upcast::<GlobalScope>(), deadline, true);
+            let _ = request
+                .callback
+                .Call__(&idle_deadline, ExceptionHandling::Report, can_gc);
+        }
+
+        // Run idle callbacks while time remains; keep the rest for the next turn.
+        idle.retain(|request| {
+            if Instant::now() >= deadline {
+                return true;
+            }
+            let idle_deadline = IdleDeadline::new(self.upcast::<GlobalScope>(), deadline, false);
+            let _ = request
+                .callback
+                .Call__(&idle_deadline, ExceptionHandling::Report, can_gc);
+            false
+        });
+
+        // Restore the unserviced callbacks ahead of any registered during this drain.
+        let mut queue = self.idle_request_callbacks.borrow_mut();
+        idle.append(&mut queue);
+        *queue = idle;
+    }
+
     pub(crate) fn layout_blocked(&self) -> bool {
         self.layout_blocker.get().layout_blocked()
     }
@@ -2694,6 +3288,29 @@ impl Window {
             };
 
             // Step 13
+            //
+            // `about:blank` must be created synchronously
+            // (<https://html.spec.whatwg.org/multipage/#read-html>), notably so that script
+            // setting `iframe.src = "about:blank"` can observe `iframe.contentDocument`
+            // immediately afterwards. Route it through a dedicated in-script path instead of
+            // the async constellation round-trip `ScriptThread::navigate` uses for every
+            // other URL; this is most load-bearing for the initial `about:blank` document of
+            // a newly created nested navigable, and for a same-document reload of one.
+            if load_data.url.as_str() == "about:blank" &&
+                (doc.is_initial_about_blank() || window_proxy.parent().is_some())
+            {
+                // `prompt_to_unload` and the delaying-load-events mode started above still
+                // apply: we're replacing the active document just as a normal navigation
+                // would, only without waiting on the constellation to hand the document back.
+                ScriptThread::navigate_about_blank_synchronously(
+                    pipeline_id,
+                    load_data,
+                    resolved_history_handling,
+                    can_gc,
+                );
+                return;
+            }
+
             ScriptThread::navigate(pipeline_id, load_data, resolved_history_handling);
         };
     }
@@ -2712,13 +3329,17 @@ impl Window {
     }
 
     /// Handle a theme change request, triggering a reflow is any actual change occured.
-    pub(crate) fn handle_theme_change(&self, new_theme: Theme) {
+    pub(crate) fn handle_theme_change(&self, new_theme: Theme, can_gc: CanGc) {
         if self.theme.get() == new_theme {
             return;
         }
         self.theme.set(new_theme);
         self.Document()
             .add_restyle_reason(RestyleReason::ThemeChanged);
+        // A theme switch flips `prefers-color-scheme`, so re-evaluate the registered
+        // media query lists and fire `change` events for any whose state changed,
+        // mirroring the resize-driven path.
+        self.evaluate_media_queries_and_report_changes(can_gc);
     }
 
     pub(crate) fn get_url(&self) -> ServoUrl {
@@ -2754,11 +3375,30 @@ impl Window {
         // is now a different size.
         self.Document()
             .add_restyle_reason(RestyleReason::ViewportSizeChanged);
+        self.invalidate_layout_value_categories(&[LayoutValueCategory::ViewportSize]);
 
-        // If viewport units were used, all nodes need to be restyled, because
-        // we currently do not track which ones rely on viewport units.
+        // If viewport units were used, restyle only the nodes known to depend on them.
         if self.layout().device().used_viewport_units() {
+            self.restyle_viewport_unit_dependent_nodes();
+        }
+    }
+
+    /// Restyles only the nodes in [`Self::viewport_unit_dependent_nodes`], i.e. those whose
+    /// computed style was last seen to read a viewport-relative unit, instead of the whole
+    /// document. Falls back to [`Document::dirty_all_nodes`] when the tracked set has grown
+    /// past [`MAX_TRACKED_VIEWPORT_UNIT_DEPENDENT_NODES`] — at that point walking it node by
+    /// node is no cheaper than a full restyle — or when it's empty, since an empty set here
+    /// means no reflow has reported one yet rather than that no node depends on viewport
+    /// units.
+    fn restyle_viewport_unit_dependent_nodes(&self) {
+        let nodes = self.viewport_unit_dependent_nodes.borrow();
+        if nodes.is_empty() || nodes.len() > MAX_TRACKED_VIEWPORT_UNIT_DEPENDENT_NODES {
+            drop(nodes);
             self.Document().dirty_all_nodes();
+            return;
+        }
+        for node in nodes.iter() {
+            node.dirty(NodeDamage::Other);
         }
     }
 
@@ -2859,11 +3499,11 @@ impl Window {
         // event is fired below so that any script queries trigger a restyle.
         self.Document()
             .add_restyle_reason(RestyleReason::ViewportSizeChanged);
+        self.invalidate_layout_value_categories(&[LayoutValueCategory::ViewportSize]);
 
-        // If viewport units were used, all nodes need to be restyled, because
-        // we currently do not track which ones rely on viewport units.
+        // If viewport units were used, restyle only the nodes known to depend on them.
         if self.layout().device().used_viewport_units() {
-            self.Document().dirty_all_nodes();
+            self.restyle_viewport_unit_dependent_nodes();
         }
 
         // http://dev.w3.org/csswg/cssom-view/#resizing-viewports
@@ -3128,6 +3768,8 @@ impl Window {
             pending_image_callbacks: Default::default(),
             pending_layout_images: Default::default(),
             pending_images_for_rasterization: Default::default(),
+            viewport_unit_dependent_nodes: Default::default(),
+            pending_image_decode_promises: Default::default(),
             unminified_css_dir: Default::default(),
             local_script_source,
             test_worklet: Default::default(),
@@ -3139,12 +3781,19 @@ impl Window {
             user_content_manager,
             player_context,
             throttled: Cell::new(false),
-            layout_marker: DomRefCell::new(Rc::new(Cell::new(true))),
+            layout_value_versions: Default::default(),
             current_event: DomRefCell::new(None),
             theme: Cell::new(theme),
             trusted_types: Default::default(),
             reporting_observer_list: Default::default(),
             report_list: Default::default(),
+            reporting_endpoints: Default::default(),
+            a11y_tree: Default::default(),
+            gamepad_registry: Default::default(),
+            user_prompt_handler: Cell::new(UserPromptHandler::default()),
+            last_dialog: Default::default(),
+            idle_request_callbacks: Default::default(),
+            smooth_scroll_animations: Default::default(),
         });
 
         unsafe {
@@ -3156,23 +3805,73 @@ impl Window {
         self.as_global_scope().pipeline_id()
     }
 
-    /// Create a new cached instance of the given value.
-    pub(crate) fn cache_layout_value<T>(&self, value: T) -> LayoutValue<T>
+    /// The shared version counter backing `category`, creating it (starting at version 0) the
+    /// first time it's read from or invalidated.
+    fn layout_value_counter(&self, category: LayoutValueCategory) -> Rc<Cell<u64>> {
+        self.layout_value_versions
+            .borrow_mut()
+            .entry(category)
+            .or_insert_with(|| Rc::new(Cell::new(0)))
+            .clone()
+    }
+
+    /// Bump the version of every category in `categories`, invalidating any [`LayoutValue`]
+    /// that recorded one of them in [`Window::cache_layout_value`]. Must be called for every
+    /// category whose underlying layout input actually changed, or a stale value could be
+    /// read back as still valid.
+    pub(crate) fn invalidate_layout_value_categories(&self, categories: &[LayoutValueCategory]) {
+        for &category in categories {
+            let counter = self.layout_value_counter(category);
+            counter.set(counter.get().wrapping_add(1));
+        }
+    }
+
+    /// Create a new cached instance of `value`, valid until any of `categories` is next
+    /// invalidated via [`Window::invalidate_layout_value_categories`].
+    pub(crate) fn cache_layout_value<T>(
+        &self,
+        value: T,
+        categories: &[LayoutValueCategory],
+    ) -> LayoutValue<T>
     where
         T: Copy + MallocSizeOf,
     {
-        LayoutValue::new(self.layout_marker.borrow().clone(), value)
+        let versions = categories
+            .iter()
+            .map(|&category| {
+                let counter = self.layout_value_counter(category);
+                let snapshot = counter.get();
+                (counter, snapshot)
+            })
+            .collect();
+        LayoutValue::new(versions, value)
     }
 }
 
-/// An instance of a value associated with a particular snapshot of layout. This stored
-/// value can only be read as long as the associated layout marker that is considered
-/// valid. It will automatically become unavailable when the next layout operation is
-/// performed.
+/// A category of layout input that invalidates previously [`Window::cache_layout_value`]d
+/// values when it changes. Kept coarse-grained, matching what layout and `Window` can cheaply
+/// report as dirtied by a reflow, rather than tracking every individual style property read.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum LayoutValueCategory {
+    /// The viewport size changed.
+    ViewportSize,
+    /// A scroll offset changed, without a full restyle.
+    ScrollOffset,
+    /// The box tree was rebuilt, i.e. a restyle happened.
+    BoxTreeGeneration,
+    /// A specific node's computed style was recalculated, keyed by its opaque id.
+    Style(u64),
+}
+
+/// An instance of a value associated with a particular snapshot of layout. This stored value
+/// can only be read back as long as none of the [`LayoutValueCategory`] versions recorded when
+/// it was cached have since been bumped by [`Window::invalidate_layout_value_categories`]; it
+/// becomes unavailable only when a layout input it actually depends on changes, rather than on
+/// every reflow.
 #[derive(MallocSizeOf)]
 pub(crate) struct LayoutValue<T: MallocSizeOf> {
     #[ignore_malloc_size_of = "Rc is hard"]
-    is_valid: Rc<Cell<bool>>,
+    versions: Vec<(Rc<Cell<u64>>, u64)>,
     value: T,
 }
 
@@ -3184,16 +3883,22 @@ unsafe impl<T: JSTraceable + MallocSizeOf> JSTraceable for LayoutValue<T> {
 }
 
 impl<T: Copy + MallocSizeOf> LayoutValue<T> {
-    fn new(marker: Rc<Cell<bool>>, value: T) -> Self {
-        LayoutValue {
-            is_valid: marker,
-            value,
-        }
+    fn new(versions: Vec<(Rc<Cell<u64>>, u64)>, value: T) -> Self {
+        LayoutValue { versions, value }
     }
 
-    /// Retrieve the stored value if it is still valid.
+    /// Retrieve the stored value if it is still valid. A value cached against no categories at
+    /// all is conservatively always treated as stale, since there's nothing recorded that
+    /// would invalidate it.
     pub(crate) fn get(&self) -> Result<T, ()> {
-        if self.is_valid.get() {
+        if self.versions.is_empty() {
+            return Err(());
+        }
+        if self
+            .versions
+            .iter()
+            .all(|(counter, snapshot)| counter.get() == *snapshot)
+        {
             return Ok(self.value);
         }
         Err(())
@@ -3272,10 +3977,13 @@ impl Window {
                 );
             }
         });
-        // TODO(#12718): Use the "posted message task source".
+        // Queued on the dedicated posted-message task source (rather than the general DOM
+        // manipulation one) so that `postMessage` deliveries keep their relative order with
+        // each other even when interleaved with unrelated DOM-manipulation tasks, matching
+        // the other `postMessage` entry points (worker and `MessagePort`).
         self.as_global_scope()
             .task_manager()
-            .dom_manipulation_task_source()
+            .posted_message_task_source()
             .queue(task);
     }
 }
@@ -0,0 +1,131 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use dom_struct::dom_struct;
+use js::jsapi::{Heap, JSObject};
+
+use crate::dom::bindings::cell::DomRefCell;
+use crate::dom::bindings::codegen::Bindings::WritableStreamBinding;
+use crate::dom::bindings::error::Error;
+use crate::dom::bindings::reflector::{reflect_dom_object, DomObject, Reflector};
+use crate::dom::bindings::root::DomRoot;
+use crate::dom::globalscope::GlobalScope;
+use crate::realms::enter_realm;
+
+/// The Rust sink that receives the chunks written to a [`WritableStream`].
+///
+/// The counterpart of [`ExternalUnderlyingSource`](super::readablestream::ExternalUnderlyingSource)
+/// on the writable side: where that lets native code feed a script-readable
+/// stream, this lets script write into a native consumer.
+pub trait ExternalUnderlyingSink: Send {
+    /// Consume a chunk of bytes. Returning an `Err` aborts the stream.
+    fn write(&self, chunk: &[u8]) -> Result<(), Error>;
+    /// The producer signalled it is done; flush and close the sink.
+    fn close(&self);
+    /// The stream was aborted with `reason`.
+    fn abort(&self, reason: &str);
+}
+
+#[dom_struct]
+#[unrooted_must_root_lint::allow_unrooted_in_rc]
+pub struct WritableStream {
+    reflector_: Reflector,
+    #[ignore_malloc_size_of = "SM handles JS values"]
+    js_stream: Heap<*mut JSObject>,
+    /// Whether a writer is currently locked to the stream.
+    has_writer: Cell<bool>,
+    #[ignore_malloc_size_of = "Trait object behind a Mutex"]
+    sink: Mutex<Box<dyn ExternalUnderlyingSink>>,
+    /// Whether [`close`](Self::close_native) or an abort has already run.
+    closed: DomRefCell<bool>,
+}
+
+impl WritableStream {
+    fn new_inherited(sink: Box<dyn ExternalUnderlyingSink>) -> WritableStream {
+        WritableStream {
+            reflector_: Reflector::new(),
+            js_stream: Heap::default(),
+            has_writer: Default::default(),
+            sink: Mutex::new(sink),
+            closed: DomRefCell::new(false),
+        }
+    }
+
+    /// Build a writable stream backed by a Rust sink.
+    pub fn new_with_external_underlying_sink(
+        global: &GlobalScope,
+        sink: Box<dyn ExternalUnderlyingSink>,
+    ) -> DomRoot<WritableStream> {
+        reflect_dom_object(
+            Box::new(WritableStream::new_inherited(sink)),
+            global,
+            WritableStreamBinding::Wrap,
+        )
+    }
+
+    /// Get a pointer to the underlying JS object.
+    pub fn get_js_stream(&self) -> Heap<*mut JSObject> {
+        let heap = Heap::default();
+        heap.set(self.js_stream.get());
+        heap
+    }
+
+    /// Forward a chunk from script to the native sink.
+    ///
+    /// Mirrors the read path of [`ReadableStream`](super::readablestream::ReadableStream):
+    /// an error from the sink aborts the stream.
+    #[allow(unsafe_code)]
+    pub fn write_native(&self, chunk: &[u8]) -> Result<(), Error> {
+        if *self.closed.borrow() {
+            return Err(Error::Type("Cannot write to a closed stream.".to_string()));
+        }
+        let global = self.global();
+        let _ar = enter_realm(&*global);
+        let result = self.sink.lock().unwrap().write(chunk);
+        if result.is_err() {
+            self.abort_native("write error");
+        }
+        result
+    }
+
+    /// Close the stream, flushing the native sink.
+    pub fn close_native(&self) {
+        if *self.closed.borrow() {
+            return;
+        }
+        *self.closed.borrow_mut() = true;
+        self.sink.lock().unwrap().close();
+    }
+
+    /// Abort the stream, discarding any queued writes.
+    pub fn abort_native(&self, reason: &str) {
+        if *self.closed.borrow() {
+            return;
+        }
+        *self.closed.borrow_mut() = true;
+        self.sink.lock().unwrap().abort(reason);
+    }
+
+    /// Acquires a writer and locks the stream.
+    pub fn start_writing(&self) -> Result<(), ()> {
+        if self.has_writer.get() {
+            return Err(());
+        }
+        self.has_writer.set(true);
+        Ok(())
+    }
+
+    /// Releases the writer lock.
+    pub fn stop_writing(&self) {
+        self.has_writer.set(false);
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.has_writer.get()
+    }
+}
@@ -8,7 +8,7 @@ use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::env;
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use euclid::{Angle, Length, Point2D, Rotation3D, Scale, Size2D, UnknownUnit, Vector2D, Vector3D};
 use keyboard_types::{Modifiers, ShortcutMatcher};
@@ -44,25 +44,74 @@ use {
 use super::app_state::RunningAppState;
 use super::geometry::{winit_position_to_euclid_point, winit_size_to_euclid_size};
 use super::keyutils::{CMD_OR_ALT, keyboard_event_from_winit};
+use super::touch::{TouchAction, TouchHandler};
 use super::window_trait::{LINE_HEIGHT, WindowPortsMethods};
 use crate::desktop::accelerated_gl_media::setup_gl_accelerated_media;
 use crate::desktop::keyutils::CMD_OR_CONTROL;
 use crate::prefs::ServoShellPreferences;
 
+/// Fractional velocity retained per `1/60`s of elapsed time while a fling
+/// animation is coasting; continuous decay is derived from this so the
+/// animation doesn't depend on `RedrawRequested` arriving at a fixed rate.
+const FLING_FRICTION_PER_TICK: f32 = 0.95;
+
+/// Below this device-pixel-per-second speed a fling is considered stopped.
+const FLING_MIN_VELOCITY: f32 = 20.0;
+
+/// In-flight momentum-scroll animation state for a `TouchAction::Fling`,
+/// decaying `velocity` by [`FLING_FRICTION_PER_TICK`] per `1/60`s of time
+/// elapsed between redraws until it drops below [`FLING_MIN_VELOCITY`].
+struct FlingState {
+    velocity: Vector2D<f32, DevicePixel>,
+    point: Point2D<f32, DevicePixel>,
+    last_tick: Instant,
+}
+
 pub struct Window {
     screen_size: Size2D<u32, DeviceIndependentPixel>,
     inner_size: Cell<PhysicalSize<u32>>,
     toolbar_height: Cell<Length<f32, DeviceIndependentPixel>>,
     monitor: winit::monitor::MonitorHandle,
     webview_relative_mouse_point: Cell<Point2D<f32, DevicePixel>>,
+    /// The most recent cursor position in physical window pixels, used for
+    /// borderless edge-hit detection.
+    mouse_physical_position: Cell<PhysicalPosition<f64>>,
     last_pressed: Cell<Option<(KeyboardEvent, Option<LogicalKey>)>>,
     /// A map of winit's key codes to key values that are interpreted from
     /// winit's ReceivedChar events.
     keys_down: RefCell<HashMap<LogicalKey, Key>>,
     fullscreen: Cell<bool>,
+    /// Whether the window was created without a native titlebar; client-side
+    /// move and edge/corner resize are only offered in this mode.
+    no_native_titlebar: bool,
     device_pixel_ratio_override: Option<f32>,
     xr_window_poses: RefCell<Vec<Rc<XRWindowPose>>>,
     modifiers_state: Cell<ModifiersState>,
+    /// The string currently being composed by the platform input method, if a
+    /// composition session is active. Cleared on commit; `Ime::Disabled` emits a
+    /// dismissal while it is set. A focus change or webview close does not.
+    composition_string: RefCell<Option<String>>,
+    /// When set, [`Self::handle_raw_mouse_motion`] forwards raw mouse deltas to
+    /// the page. No servoshell event handler routes `DeviceEvent::MouseMotion`
+    /// to it, so the page receives the coalesced `WindowEvent::CursorMoved`
+    /// stream whether or not this is set.
+    raw_mouse_motion: bool,
+    /// Recognizes pan/fling/tap gestures from the raw winit touch stream.
+    touch_handler: RefCell<TouchHandler>,
+    /// The momentum-scroll animation kicked off by the most recent
+    /// `TouchAction::Fling`, if it hasn't yet decayed to a stop.
+    fling: Cell<Option<FlingState>>,
+    /// Whether [`Self::set_pointer_locked`] currently holds the cursor. While
+    /// locked, the cursor is hidden and grabbed. Nothing in servoshell calls
+    /// `set_pointer_locked`, so this stays `false`.
+    pointer_locked: Cell<bool>,
+    /// The most recently observed OS color scheme, forwarded to webviews so
+    /// that `prefers-color-scheme` media queries match the platform.
+    current_theme: Cell<Theme>,
+    /// Custom cursor images (`cursor: url(...)`) keyed by their pixel data and
+    /// hotspot, so that re-using the same cursor does not re-upload it to the
+    /// platform on every `set_cursor`.
+    custom_cursors: RefCell<HashMap<CustomCursorKey, winit::window::CustomCursor>>,
 
     /// The RenderingContext that renders directly onto the Window. This is used as
     /// the target of egui rendering and also where Servo rendering results are finally
@@ -146,19 +195,33 @@ impl Window {
 
         let rendering_context = Rc::new(window_rendering_context.offscreen_context(inner_size));
 
+        let current_theme = match winit_window.theme() {
+            Some(winit::window::Theme::Dark) => Theme::Dark,
+            Some(winit::window::Theme::Light) | None => Theme::Light,
+        };
+
         debug!("Created window {:?}", winit_window.id());
         Window {
             winit_window,
+            current_theme: Cell::new(current_theme),
+            raw_mouse_motion: servoshell_preferences.raw_mouse_motion,
+            touch_handler: RefCell::new(TouchHandler::new()),
+            fling: Cell::new(None),
+            pointer_locked: Cell::new(false),
             webview_relative_mouse_point: Cell::new(Point2D::zero()),
+            mouse_physical_position: Cell::new(PhysicalPosition::new(0.0, 0.0)),
             last_pressed: Cell::new(None),
             keys_down: RefCell::new(HashMap::new()),
             fullscreen: Cell::new(false),
+            no_native_titlebar,
             inner_size: Cell::new(inner_size),
             monitor,
             screen_size,
             device_pixel_ratio_override: servoshell_preferences.device_pixel_ratio_override,
             xr_window_poses: RefCell::new(vec![]),
             modifiers_state: Cell::new(ModifiersState::empty()),
+            composition_string: RefCell::new(None),
+            custom_cursors: RefCell::new(HashMap::new()),
             toolbar_height: Cell::new(Default::default()),
             window_rendering_context,
             rendering_context,
@@ -207,6 +270,64 @@ impl Window {
         webview.notify_input_event(InputEvent::Keyboard(event));
     }
 
+    /// Translate winit's IME lifecycle into Servo composition input events.
+    ///
+    /// Unlike [`Self::handle_received_character`], which only copes with
+    /// committed characters and dead-key accent combining, this path supports
+    /// composing input methods (CJK, etc.): `Ime::Enabled` starts a composition
+    /// session, `Ime::Preedit` forwards the in-progress string plus its
+    /// selection/caret range, `Ime::Commit` finalizes it, and `Ime::Disabled`
+    /// dismisses any active composition.
+    fn handle_ime(&self, webview: &WebView, ime: Ime) {
+        match ime {
+            Ime::Enabled => {
+                *self.composition_string.borrow_mut() = Some(String::new());
+                webview.notify_input_event(InputEvent::Ime(ImeEvent::Composition(
+                    servo::CompositionEvent {
+                        state: servo::CompositionState::Start,
+                        data: String::new(),
+                        selection: None,
+                    },
+                )));
+            },
+            Ime::Preedit(text, range) => {
+                *self.composition_string.borrow_mut() = Some(text.clone());
+                // `range` is the selection/caret byte offsets within the
+                // pre-edit string (`None` means the composition has no caret,
+                // e.g. it is being hidden). Forward it so the webview can place
+                // the caret and render the selected sub-range of the preedit.
+                webview.notify_input_event(InputEvent::Ime(ImeEvent::Composition(
+                    servo::CompositionEvent {
+                        state: servo::CompositionState::Update,
+                        data: text,
+                        selection: range,
+                    },
+                )));
+            },
+            Ime::Commit(text) => {
+                *self.composition_string.borrow_mut() = None;
+                webview.notify_input_event(InputEvent::Ime(ImeEvent::Composition(
+                    servo::CompositionEvent {
+                        state: servo::CompositionState::End,
+                        data: text,
+                        selection: None,
+                    },
+                )));
+            },
+            Ime::Disabled => {
+                self.cancel_composition(webview);
+            },
+        }
+    }
+
+    /// Dismiss any in-progress composition; called on `Ime::Disabled`. A no-op
+    /// if no composition is active.
+    fn cancel_composition(&self, webview: &WebView) {
+        if self.composition_string.borrow_mut().take().is_some() {
+            webview.notify_input_event(InputEvent::Ime(ImeEvent::Dismissed));
+        }
+    }
+
     fn handle_keyboard_input(&self, state: Rc<RunningAppState>, winit_event: KeyEvent) {
         // First, handle servoshell key bindings that are not overridable by, or visible to, the page.
         let mut keyboard_event =
@@ -280,6 +401,67 @@ impl Window {
         )));
     }
 
+    /// The width, in physical pixels, of the invisible border along a
+    /// borderless window's edges that initiates an interactive resize.
+    const RESIZE_BORDER: f64 = 8.0;
+
+    /// If `point` (in physical pixels, relative to the window) lies within the
+    /// resize border of a borderless window, return the edge/corner it would
+    /// resize.
+    fn resize_direction_at(
+        &self,
+        point: PhysicalPosition<f64>,
+    ) -> Option<winit::window::ResizeDirection> {
+        use winit::window::ResizeDirection;
+
+        if !self.no_native_titlebar {
+            return None;
+        }
+
+        let size = self.inner_size.get();
+        let border = Self::RESIZE_BORDER;
+        let west = point.x <= border;
+        let east = point.x >= size.width as f64 - border;
+        let north = point.y <= border;
+        let south = point.y >= size.height as f64 - border;
+
+        match (north, south, west, east) {
+            (true, _, true, _) => Some(ResizeDirection::NorthWest),
+            (true, _, _, true) => Some(ResizeDirection::NorthEast),
+            (_, true, true, _) => Some(ResizeDirection::SouthWest),
+            (_, true, _, true) => Some(ResizeDirection::SouthEast),
+            (true, _, _, _) => Some(ResizeDirection::North),
+            (_, true, _, _) => Some(ResizeDirection::South),
+            (_, _, true, _) => Some(ResizeDirection::West),
+            (_, _, _, true) => Some(ResizeDirection::East),
+            _ => None,
+        }
+    }
+
+    /// Begin a client-side move or edge/corner resize in response to a left
+    /// button press in a borderless window. Returns `true` if the press was
+    /// consumed to start a drag, in which case it must not be forwarded to the
+    /// page.
+    fn handle_borderless_drag(&self, point: PhysicalPosition<f64>) -> bool {
+        if !self.no_native_titlebar {
+            return false;
+        }
+
+        if let Some(direction) = self.resize_direction_at(point) {
+            let _ = self.winit_window.drag_resize_window(direction);
+            return true;
+        }
+
+        // A press in the toolbar area (above the webview) moves the window.
+        let toolbar = (self.toolbar_height() * self.hidpi_scale_factor()).0 as f64;
+        if point.y <= toolbar {
+            let _ = self.winit_window.drag_window();
+            return true;
+        }
+
+        false
+    }
+
     /// Handle key events before sending them to Servo.
     fn handle_intercepted_key_bindings(
         &self,
@@ -401,10 +583,139 @@ impl Window {
         handled
     }
 
+    /// Apply a CSS `cursor: url(...)` custom cursor image.
+    ///
+    /// The decoded RGBA image plus its hotspot is uploaded to the platform via
+    /// winit's [`CustomCursor`](winit::window::CustomCursor) machinery (which
+    /// requires the event loop to build the source) and cached so that the same
+    /// image is not re-uploaded on subsequent hits.
+    pub(crate) fn set_custom_cursor(
+        &self,
+        event_loop: &ActiveEventLoop,
+        rgba: Vec<u8>,
+        width: u16,
+        height: u16,
+        hotspot_x: u16,
+        hotspot_y: u16,
+    ) {
+        let key = CustomCursorKey {
+            rgba,
+            width,
+            height,
+            hotspot_x,
+            hotspot_y,
+        };
+        let cursor = self
+            .custom_cursors
+            .borrow_mut()
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let source = winit::window::CustomCursor::from_rgba(
+                    key.rgba.clone(),
+                    width,
+                    height,
+                    hotspot_x,
+                    hotspot_y,
+                )
+                .expect("Failed to build custom cursor from image");
+                event_loop.create_custom_cursor(source)
+            })
+            .clone();
+        self.winit_window.set_cursor(cursor);
+        self.winit_window.set_cursor_visible(true);
+    }
+
     pub(crate) fn offscreen_rendering_context(&self) -> Rc<OffscreenRenderingContext> {
         self.rendering_context.clone()
     }
 
+    /// Engage or release Pointer Lock in response to the Pointer Lock API.
+    ///
+    /// Locking grabs the cursor to the window and hides it; raw motion is then
+    /// delivered through [`Self::handle_raw_mouse_motion`]. Releasing restores
+    /// the ordinary cursor. Falls back from `Locked` to `Confined` grab on
+    /// platforms (X11) that do not support locking.
+    pub(crate) fn set_pointer_locked(&self, locked: bool) {
+        use winit::window::CursorGrabMode;
+
+        if self.pointer_locked.get() == locked {
+            return;
+        }
+
+        if locked {
+            let grabbed = self
+                .winit_window
+                .set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| self.winit_window.set_cursor_grab(CursorGrabMode::Confined));
+            if grabbed.is_ok() {
+                self.winit_window.set_cursor_visible(false);
+                self.pointer_locked.set(true);
+            }
+        } else {
+            let _ = self.winit_window.set_cursor_grab(CursorGrabMode::None);
+            self.winit_window.set_cursor_visible(true);
+            self.pointer_locked.set(false);
+        }
+    }
+
+    /// Deliver raw, un-coalesced pointer motion from a
+    /// [`DeviceEvent::MouseMotion`](winit::event::DeviceEvent::MouseMotion).
+    ///
+    /// Acts only when `raw_mouse_motion` is configured or Pointer Lock is
+    /// active; the coalesced `CursorMoved` path is unaffected either way.
+    pub(crate) fn handle_raw_mouse_motion(&self, state: &RunningAppState, delta: (f64, f64)) {
+        // Raw motion is delivered either when explicitly configured or whenever
+        // Pointer Lock is active (the lock relies on relative motion).
+        if !self.raw_mouse_motion && !self.pointer_locked.get() {
+            return;
+        }
+        let Some(webview) = state.focused_webview() else {
+            return;
+        };
+        // Accumulate the raw delta onto the last known position; the page
+        // receives every sample rather than one coalesced move per frame.
+        // Raw motion doubles as WebXR mouse-look: rotate every active XR pose.
+        let xr_poses = self.xr_window_poses.borrow();
+        if !xr_poses.is_empty() {
+            for pose in &*xr_poses {
+                pose.handle_xr_mouse_look(delta);
+            }
+        }
+
+        let mut point = self.webview_relative_mouse_point.get();
+        point.x += delta.0 as f32;
+        point.y += delta.1 as f32;
+        self.webview_relative_mouse_point.set(point);
+        webview.notify_input_event(InputEvent::MouseMove(MouseMoveEvent::new(point)));
+    }
+
+    /// Request that the window manager give this window input focus.
+    ///
+    /// On Wayland and X11 raising/focusing a window is not permitted unless it
+    /// carries a valid XDG activation / startup-notification token. When Servo
+    /// is launched (or asked to open a new tab) with such a token in the
+    /// environment, consume it to activate the window; otherwise fall back to
+    /// winit's best-effort [`focus_window`](winit::window::Window::focus_window).
+    pub(crate) fn request_activation(&self, event_loop: &ActiveEventLoop) {
+        #[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "dragonfly"))]
+        {
+            use winit::platform::startup_notify::{
+                self, EventLoopExtStartupNotify, WindowExtStartupNotify,
+            };
+
+            if let Some(token) = event_loop.read_token_from_env() {
+                // The token is single-use; drop it from the environment so a
+                // later window does not try to reuse a spent token.
+                startup_notify::reset_activation_token_env();
+                if self.winit_window.activate_with_token(token).is_ok() {
+                    return;
+                }
+            }
+        }
+        let _ = event_loop;
+        self.winit_window.focus_window();
+    }
+
     #[allow(unused_variables)]
     fn force_srgb_color_space(window_handle: RawWindowHandle) {
         #[cfg(target_os = "macos")]
@@ -519,50 +830,18 @@ impl WindowPortsMethods for Window {
     }
 
     fn set_cursor(&self, cursor: Cursor) {
-        use winit::window::CursorIcon;
-
-        let winit_cursor = match cursor {
-            Cursor::Default => CursorIcon::Default,
-            Cursor::Pointer => CursorIcon::Pointer,
-            Cursor::ContextMenu => CursorIcon::ContextMenu,
-            Cursor::Help => CursorIcon::Help,
-            Cursor::Progress => CursorIcon::Progress,
-            Cursor::Wait => CursorIcon::Wait,
-            Cursor::Cell => CursorIcon::Cell,
-            Cursor::Crosshair => CursorIcon::Crosshair,
-            Cursor::Text => CursorIcon::Text,
-            Cursor::VerticalText => CursorIcon::VerticalText,
-            Cursor::Alias => CursorIcon::Alias,
-            Cursor::Copy => CursorIcon::Copy,
-            Cursor::Move => CursorIcon::Move,
-            Cursor::NoDrop => CursorIcon::NoDrop,
-            Cursor::NotAllowed => CursorIcon::NotAllowed,
-            Cursor::Grab => CursorIcon::Grab,
-            Cursor::Grabbing => CursorIcon::Grabbing,
-            Cursor::EResize => CursorIcon::EResize,
-            Cursor::NResize => CursorIcon::NResize,
-            Cursor::NeResize => CursorIcon::NeResize,
-            Cursor::NwResize => CursorIcon::NwResize,
-            Cursor::SResize => CursorIcon::SResize,
-            Cursor::SeResize => CursorIcon::SeResize,
-            Cursor::SwResize => CursorIcon::SwResize,
-            Cursor::WResize => CursorIcon::WResize,
-            Cursor::EwResize => CursorIcon::EwResize,
-            Cursor::NsResize => CursorIcon::NsResize,
-            Cursor::NeswResize => CursorIcon::NeswResize,
-            Cursor::NwseResize => CursorIcon::NwseResize,
-            Cursor::ColResize => CursorIcon::ColResize,
-            Cursor::RowResize => CursorIcon::RowResize,
-            Cursor::AllScroll => CursorIcon::AllScroll,
-            Cursor::ZoomIn => CursorIcon::ZoomIn,
-            Cursor::ZoomOut => CursorIcon::ZoomOut,
-            Cursor::None => {
-                self.winit_window.set_cursor_visible(false);
-                return;
+        // Pointer Lock hides the cursor regardless of the page's `cursor` style.
+        if self.pointer_locked.get() {
+            return;
+        }
+        match cursor_to_winit(cursor) {
+            Some(winit_cursor) => {
+                self.winit_window.set_cursor(winit_cursor);
+                self.winit_window.set_cursor_visible(true);
             },
-        };
-        self.winit_window.set_cursor(winit_cursor);
-        self.winit_window.set_cursor_visible(true);
+            // `Cursor::None` hides the pointer.
+            None => self.winit_window.set_cursor_visible(false),
+        }
     }
 
     fn id(&self) -> winit::window::WindowId {
@@ -578,11 +857,29 @@ impl WindowPortsMethods for Window {
             WindowEvent::KeyboardInput { event, .. } => self.handle_keyboard_input(state, event),
             WindowEvent::ModifiersChanged(modifiers) => self.modifiers_state.set(modifiers.state()),
             WindowEvent::MouseInput { state, button, .. } => {
+                // In borderless mode, a left press on an edge/corner or the
+                // toolbar starts a client-side resize or move instead of being
+                // delivered to the page.
+                if button == MouseButton::Left &&
+                    state == ElementState::Pressed &&
+                    self.handle_borderless_drag(self.mouse_physical_position.get())
+                {
+                    return;
+                }
                 if button == MouseButton::Left || button == MouseButton::Right {
                     self.handle_mouse(&webview, button, state);
                 }
             },
             WindowEvent::CursorMoved { position, .. } => {
+                self.mouse_physical_position.set(position);
+                // In borderless mode, show the matching resize cursor while the
+                // pointer hovers an edge or corner so the resize affordance is
+                // discoverable.
+                if let Some(direction) = self.resize_direction_at(position) {
+                    self.winit_window.set_cursor(resize_direction_to_cursor(direction));
+                    self.winit_window.set_cursor_visible(true);
+                    return;
+                }
                 let mut point = winit_position_to_euclid_point(position).to_f32();
                 point.y -= (self.toolbar_height() * self.hidpi_scale_factor()).0;
 
@@ -638,11 +935,53 @@ impl WindowPortsMethods for Window {
                 webview.notify_scroll_event(scroll_location, point.to_i32());
             },
             WindowEvent::Touch(touch) => {
+                let point = Point2D::new(touch.location.x as f32, touch.location.y as f32);
+
+                // Still forward the raw touch event so the page can run its own
+                // touch handlers; in addition, recognize gestures and turn them
+                // into scroll events for default scrolling behaviour.
                 webview.notify_input_event(InputEvent::Touch(TouchEvent::new(
                     winit_phase_to_touch_event_type(touch.phase),
                     TouchId(touch.id as i32),
-                    Point2D::new(touch.location.x as f32, touch.location.y as f32),
+                    point,
                 )));
+
+                let mut handler = self.touch_handler.borrow_mut();
+                let action = match touch.phase {
+                    TouchPhase::Started => {
+                        handler.on_touch_down(touch.id, point);
+                        TouchAction::None
+                    },
+                    TouchPhase::Moved => handler.on_touch_move(touch.id, point),
+                    TouchPhase::Ended => handler.on_touch_up(touch.id, point),
+                    TouchPhase::Cancelled => {
+                        handler.on_touch_cancel(touch.id);
+                        TouchAction::None
+                    },
+                };
+                drop(handler);
+
+                match action {
+                    TouchAction::Pan(delta) => {
+                        webview.notify_scroll_event(
+                            ScrollLocation::Delta(Vector2D::new(delta.x, delta.y)),
+                            point.to_i32(),
+                        );
+                    },
+                    TouchAction::Fling(velocity) => {
+                        // `velocity` is a per-second estimate, not a single
+                        // frame's delta: kick off a decaying momentum-scroll
+                        // animation (ticked from `RedrawRequested`) rather
+                        // than applying a whole second of movement at once.
+                        self.fling.set(Some(FlingState {
+                            velocity,
+                            point,
+                            last_tick: Instant::now(),
+                        }));
+                        self.winit_window.request_redraw();
+                    },
+                    TouchAction::Tap(_) | TouchAction::None => {},
+                }
             },
             WindowEvent::PinchGesture { delta, .. } => {
                 webview.set_pinch_zoom(delta as f32 + 1.0);
@@ -656,40 +995,52 @@ impl WindowPortsMethods for Window {
                     self.inner_size.set(new_size);
                 }
             },
+            WindowEvent::ScaleFactorChanged { .. } => {
+                // The HiDPI factor changed live (e.g. the window moved to a
+                // monitor with a different scale). winit has already queued a
+                // `Resized` with the new physical size, but we still need to
+                // pick up the new device-pixel-ratio and resize the rendering
+                // context to the window's current inner size.
+                let new_size = self.winit_window.inner_size();
+                if self.inner_size.get() != new_size {
+                    self.window_rendering_context.resize(new_size);
+                    self.inner_size.set(new_size);
+                }
+            },
             WindowEvent::ThemeChanged(theme) => {
-                webview.notify_theme_change(match theme {
+                let theme = match theme {
                     winit::window::Theme::Light => Theme::Light,
                     winit::window::Theme::Dark => Theme::Dark,
-                });
+                };
+                self.current_theme.set(theme);
+                // Forward to every webview, not just the focused one, so that
+                // background tabs also see the updated `prefers-color-scheme`.
+                for webview in state.webviews() {
+                    webview.notify_theme_change(theme);
+                }
             },
-            WindowEvent::Ime(ime) => match ime {
-                Ime::Enabled => {
-                    webview.notify_input_event(InputEvent::Ime(ImeEvent::Composition(
-                        servo::CompositionEvent {
-                            state: servo::CompositionState::Start,
-                            data: String::new(),
-                        },
-                    )));
-                },
-                Ime::Preedit(text, _) => {
-                    webview.notify_input_event(InputEvent::Ime(ImeEvent::Composition(
-                        servo::CompositionEvent {
-                            state: servo::CompositionState::Update,
-                            data: text,
-                        },
-                    )));
-                },
-                Ime::Commit(text) => {
-                    webview.notify_input_event(InputEvent::Ime(ImeEvent::Composition(
-                        servo::CompositionEvent {
-                            state: servo::CompositionState::End,
-                            data: text,
-                        },
-                    )));
-                },
-                Ime::Disabled => {
-                    webview.notify_input_event(InputEvent::Ime(ImeEvent::Dismissed));
-                },
+            WindowEvent::Ime(ime) => self.handle_ime(&webview, ime),
+            WindowEvent::RedrawRequested => {
+                if let Some(mut fling) = self.fling.take() {
+                    let elapsed = fling.last_tick.elapsed().as_secs_f32();
+                    let delta = fling.velocity * elapsed;
+                    webview.notify_scroll_event(
+                        ScrollLocation::Delta(Vector2D::new(delta.x, delta.y)),
+                        fling.point.to_i32(),
+                    );
+
+                    // Decay continuously rather than by a fixed per-frame
+                    // factor, so the animation doesn't speed up or slow down
+                    // if redraws arrive off the nominal 60Hz cadence.
+                    let decay = FLING_FRICTION_PER_TICK.powf(elapsed * 60.0);
+                    fling.velocity *= decay;
+                    fling.last_tick = Instant::now();
+
+                    if fling.velocity.length() > FLING_MIN_VELOCITY {
+                        self.fling.set(Some(fling));
+                        self.winit_window.request_redraw();
+                    }
+                }
             },
             _ => {},
         }
@@ -713,6 +1064,7 @@ impl WindowPortsMethods for Window {
         let pose = Rc::new(XRWindowPose {
             xr_rotation: Cell::new(Rotation3D::identity()),
             xr_translation: Cell::new(Vector3D::zero()),
+            bindings: XRBindings::default(),
         });
         self.xr_window_poses.borrow_mut().push(pose.clone());
         Rc::new(XRWindow { winit_window, pose })
@@ -772,6 +1124,77 @@ impl WindowPortsMethods for Window {
     }
 }
 
+/// Identity of a custom cursor image, used to cache uploaded
+/// [`CustomCursor`](winit::window::CustomCursor)s.
+#[derive(Clone, Eq, Hash, PartialEq)]
+struct CustomCursorKey {
+    rgba: Vec<u8>,
+    width: u16,
+    height: u16,
+    hotspot_x: u16,
+    hotspot_y: u16,
+}
+
+/// Map a CSS `cursor` keyword (as a Servo [`Cursor`]) to the corresponding
+/// winit [`CursorIcon`](winit::window::CursorIcon). `Cursor::None` maps to
+/// `None`, signalling that the pointer should be hidden.
+fn cursor_to_winit(cursor: Cursor) -> Option<winit::window::CursorIcon> {
+    use winit::window::CursorIcon;
+
+    Some(match cursor {
+        Cursor::Default => CursorIcon::Default,
+        Cursor::Pointer => CursorIcon::Pointer,
+        Cursor::ContextMenu => CursorIcon::ContextMenu,
+        Cursor::Help => CursorIcon::Help,
+        Cursor::Progress => CursorIcon::Progress,
+        Cursor::Wait => CursorIcon::Wait,
+        Cursor::Cell => CursorIcon::Cell,
+        Cursor::Crosshair => CursorIcon::Crosshair,
+        Cursor::Text => CursorIcon::Text,
+        Cursor::VerticalText => CursorIcon::VerticalText,
+        Cursor::Alias => CursorIcon::Alias,
+        Cursor::Copy => CursorIcon::Copy,
+        Cursor::Move => CursorIcon::Move,
+        Cursor::NoDrop => CursorIcon::NoDrop,
+        Cursor::NotAllowed => CursorIcon::NotAllowed,
+        Cursor::Grab => CursorIcon::Grab,
+        Cursor::Grabbing => CursorIcon::Grabbing,
+        Cursor::EResize => CursorIcon::EResize,
+        Cursor::NResize => CursorIcon::NResize,
+        Cursor::NeResize => CursorIcon::NeResize,
+        Cursor::NwResize => CursorIcon::NwResize,
+        Cursor::SResize => CursorIcon::SResize,
+        Cursor::SeResize => CursorIcon::SeResize,
+        Cursor::SwResize => CursorIcon::SwResize,
+        Cursor::WResize => CursorIcon::WResize,
+        Cursor::EwResize => CursorIcon::EwResize,
+        Cursor::NsResize => CursorIcon::NsResize,
+        Cursor::NeswResize => CursorIcon::NeswResize,
+        Cursor::NwseResize => CursorIcon::NwseResize,
+        Cursor::ColResize => CursorIcon::ColResize,
+        Cursor::RowResize => CursorIcon::RowResize,
+        Cursor::AllScroll => CursorIcon::AllScroll,
+        Cursor::ZoomIn => CursorIcon::ZoomIn,
+        Cursor::ZoomOut => CursorIcon::ZoomOut,
+        Cursor::None => return None,
+    })
+}
+
+/// The resize cursor to show while hovering a given edge/corner of an
+/// undecorated window.
+fn resize_direction_to_cursor(
+    direction: winit::window::ResizeDirection,
+) -> winit::window::CursorIcon {
+    use winit::window::{CursorIcon, ResizeDirection};
+
+    match direction {
+        ResizeDirection::North | ResizeDirection::South => CursorIcon::NsResize,
+        ResizeDirection::East | ResizeDirection::West => CursorIcon::EwResize,
+        ResizeDirection::NorthEast | ResizeDirection::SouthWest => CursorIcon::NeswResize,
+        ResizeDirection::NorthWest | ResizeDirection::SouthEast => CursorIcon::NwseResize,
+    }
+}
+
 fn winit_phase_to_touch_event_type(phase: TouchPhase) -> TouchEventType {
     match phase {
         TouchPhase::Started => TouchEventType::Down,
@@ -801,9 +1224,38 @@ struct XRWindow {
     pose: Rc<XRWindowPose>,
 }
 
+/// Configurable key/mouse bindings for the six-degrees-of-freedom WebXR
+/// camera control. Defaults to WASD translation and a modest mouse-look
+/// sensitivity; a future preference can override any of these.
+struct XRBindings {
+    forward: char,
+    backward: char,
+    left: char,
+    right: char,
+    up: char,
+    down: char,
+    /// Radians of rotation per device pixel of raw mouse motion.
+    mouse_sensitivity: f32,
+}
+
+impl Default for XRBindings {
+    fn default() -> Self {
+        XRBindings {
+            forward: 'w',
+            backward: 's',
+            left: 'a',
+            right: 'd',
+            up: 'e',
+            down: 'q',
+            mouse_sensitivity: 0.005,
+        }
+    }
+}
+
 struct XRWindowPose {
     xr_rotation: Cell<Rotation3D<f32, UnknownUnit, UnknownUnit>>,
     xr_translation: Cell<Vector3D<f32, UnknownUnit>>,
+    bindings: XRBindings,
 }
 
 impl servo::webxr::glwindow::GlWindow for XRWindow {
@@ -861,26 +1313,55 @@ impl XRWindowPose {
         const NORMAL_TRANSLATE: f32 = 0.1;
         const QUICK_TRANSLATE: f32 = 1.0;
         let mut x = 0.0;
+        let mut y = 0.0;
         let mut z = 0.0;
-        match input.event.key {
-            Key::Character(ref k) => match &**k {
-                "w" => z = -NORMAL_TRANSLATE,
-                "W" => z = -QUICK_TRANSLATE,
-                "s" => z = NORMAL_TRANSLATE,
-                "S" => z = QUICK_TRANSLATE,
-                "a" => x = -NORMAL_TRANSLATE,
-                "A" => x = -QUICK_TRANSLATE,
-                "d" => x = NORMAL_TRANSLATE,
-                "D" => x = QUICK_TRANSLATE,
-                _ => return,
-            },
-            _ => return,
+        let Key::Character(ref k) = input.event.key else {
+            return;
+        };
+        // A single key per character; the shift-modified (uppercase) form
+        // translates further in the same direction.
+        let Some(c) = k.chars().next() else {
+            return;
         };
+        let magnitude = if c.is_uppercase() {
+            QUICK_TRANSLATE
+        } else {
+            NORMAL_TRANSLATE
+        };
+        let c = c.to_ascii_lowercase();
+        let b = &self.bindings;
+        if c == b.forward {
+            z = -magnitude;
+        } else if c == b.backward {
+            z = magnitude;
+        } else if c == b.left {
+            x = -magnitude;
+        } else if c == b.right {
+            x = magnitude;
+        } else if c == b.up {
+            y = magnitude;
+        } else if c == b.down {
+            y = -magnitude;
+        } else {
+            return;
+        }
         let (old_x, old_y, old_z) = self.xr_translation.get().to_tuple();
-        let vec = Vector3D::new(x + old_x, old_y, z + old_z);
+        let vec = Vector3D::new(x + old_x, y + old_y, z + old_z);
         self.xr_translation.set(vec);
     }
 
+    /// Apply raw mouse motion as a yaw/pitch look rotation, giving the WebXR
+    /// camera the rotational half of its six degrees of freedom.
+    fn handle_xr_mouse_look(&self, delta: (f64, f64)) {
+        let sensitivity = self.bindings.mouse_sensitivity;
+        let yaw: Rotation3D<_, UnknownUnit, UnknownUnit> =
+            Rotation3D::around_y(Angle::radians(-delta.0 as f32 * sensitivity));
+        let pitch: Rotation3D<_, UnknownUnit, UnknownUnit> =
+            Rotation3D::around_x(Angle::radians(-delta.1 as f32 * sensitivity));
+        let rotation = self.xr_rotation.get().then(&pitch).then(&yaw);
+        self.xr_rotation.set(rotation);
+    }
+
     fn handle_xr_rotation(&self, input: &KeyEvent, modifiers: ModifiersState) {
         if input.state != ElementState::Pressed {
             return;
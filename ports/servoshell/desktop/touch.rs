@@ -0,0 +1,195 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Turn the raw, per-finger touch stream winit delivers into higher-level
+//! pan/fling/tap gestures before they reach a webview.
+//!
+//! winit reports every touch point as an independent down/move/up sequence.
+//! This handler tracks the active points, distinguishes a tap (a quick
+//! down/up with little movement) from a pan (a sustained single-finger drag),
+//! and estimates a fling velocity from the final moves so momentum scrolling
+//! can continue after the finger lifts.
+
+use euclid::{Point2D, Vector2D};
+use servo::webrender_api::units::DevicePixel;
+
+/// The maximum distance, in device pixels, a touch may travel and still be
+/// considered a tap rather than a pan.
+const TAP_MAX_MOVEMENT: f32 = 10.0;
+
+/// A gesture recognized from the raw touch stream.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TouchAction {
+    /// A single-finger drag by the given delta.
+    Pan(Vector2D<f32, DevicePixel>),
+    /// The finger lifted after a pan; continue scrolling with this velocity
+    /// (device pixels per second).
+    Fling(Vector2D<f32, DevicePixel>),
+    /// A tap at the given point.
+    Tap(Point2D<f32, DevicePixel>),
+    /// Nothing actionable yet.
+    None,
+}
+
+/// A single active touch point.
+struct ActiveTouch {
+    id: u64,
+    start: Point2D<f32, DevicePixel>,
+    last: Point2D<f32, DevicePixel>,
+    /// The most recent move delta, used to estimate fling velocity.
+    last_delta: Vector2D<f32, DevicePixel>,
+    /// Whether this touch has moved far enough to count as a pan.
+    moved: bool,
+}
+
+#[derive(Default)]
+pub struct TouchHandler {
+    active: Vec<ActiveTouch>,
+}
+
+impl TouchHandler {
+    pub fn new() -> TouchHandler {
+        TouchHandler::default()
+    }
+
+    /// A finger touched down.
+    pub fn on_touch_down(&mut self, id: u64, point: Point2D<f32, DevicePixel>) {
+        self.active.push(ActiveTouch {
+            id,
+            start: point,
+            last: point,
+            last_delta: Vector2D::zero(),
+            moved: false,
+        });
+    }
+
+    /// A finger moved; returns the pan gesture, if any. Multi-finger gestures
+    /// (pinch/zoom) are left to the compositor and produce no pan here.
+    pub fn on_touch_move(
+        &mut self,
+        id: u64,
+        point: Point2D<f32, DevicePixel>,
+    ) -> TouchAction {
+        let multi_touch = self.active.len() > 1;
+        let Some(touch) = self.active.iter_mut().find(|t| t.id == id) else {
+            return TouchAction::None;
+        };
+
+        let delta = point - touch.last;
+        touch.last = point;
+        touch.last_delta = delta;
+        if (point - touch.start).length() > TAP_MAX_MOVEMENT {
+            touch.moved = true;
+        }
+
+        if multi_touch || !touch.moved || delta == Vector2D::zero() {
+            TouchAction::None
+        } else {
+            TouchAction::Pan(delta)
+        }
+    }
+
+    /// A finger lifted; returns a tap, a fling, or nothing.
+    pub fn on_touch_up(&mut self, id: u64, point: Point2D<f32, DevicePixel>) -> TouchAction {
+        let Some(index) = self.active.iter().position(|t| t.id == id) else {
+            return TouchAction::None;
+        };
+        let touch = self.active.remove(index);
+
+        if !touch.moved && (point - touch.start).length() <= TAP_MAX_MOVEMENT {
+            TouchAction::Tap(point)
+        } else if touch.last_delta != Vector2D::zero() {
+            // Approximate a per-second velocity from the last move delta,
+            // assuming roughly 60 samples per second.
+            TouchAction::Fling(touch.last_delta * 60.0)
+        } else {
+            TouchAction::None
+        }
+    }
+
+    /// A touch sequence was canceled (e.g. the window lost focus).
+    pub fn on_touch_cancel(&mut self, id: u64) {
+        self.active.retain(|t| t.id != id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f32, y: f32) -> Point2D<f32, DevicePixel> {
+        Point2D::new(x, y)
+    }
+
+    #[test]
+    fn small_movement_is_a_tap() {
+        let mut handler = TouchHandler::new();
+        handler.on_touch_down(1, point(0.0, 0.0));
+        assert_eq!(
+            handler.on_touch_move(1, point(2.0, 2.0)),
+            TouchAction::None
+        );
+        assert_eq!(handler.on_touch_up(1, point(2.0, 2.0)), TouchAction::Tap(point(2.0, 2.0)));
+    }
+
+    #[test]
+    fn sustained_drag_is_a_pan() {
+        let mut handler = TouchHandler::new();
+        handler.on_touch_down(1, point(0.0, 0.0));
+        // Cross the tap-movement threshold.
+        assert_eq!(
+            handler.on_touch_move(1, point(20.0, 0.0)),
+            TouchAction::Pan(Vector2D::new(20.0, 0.0))
+        );
+        // Further moves keep panning by their incremental delta.
+        assert_eq!(
+            handler.on_touch_move(1, point(25.0, 0.0)),
+            TouchAction::Pan(Vector2D::new(5.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn lifting_after_a_pan_estimates_a_fling() {
+        let mut handler = TouchHandler::new();
+        handler.on_touch_down(1, point(0.0, 0.0));
+        handler.on_touch_move(1, point(20.0, 0.0));
+        handler.on_touch_move(1, point(25.0, 0.0));
+        assert_eq!(
+            handler.on_touch_up(1, point(25.0, 0.0)),
+            TouchAction::Fling(Vector2D::new(5.0, 0.0) * 60.0)
+        );
+    }
+
+    #[test]
+    fn lifting_without_a_final_delta_produces_no_fling() {
+        let mut handler = TouchHandler::new();
+        handler.on_touch_down(1, point(0.0, 0.0));
+        handler.on_touch_move(1, point(20.0, 0.0));
+        // The finger stops moving before lifting: no fresh delta to fling with.
+        assert_eq!(
+            handler.on_touch_move(1, point(20.0, 0.0)),
+            TouchAction::None
+        );
+        assert_eq!(handler.on_touch_up(1, point(20.0, 0.0)), TouchAction::None);
+    }
+
+    #[test]
+    fn two_finger_moves_produce_no_pan() {
+        let mut handler = TouchHandler::new();
+        handler.on_touch_down(1, point(0.0, 0.0));
+        handler.on_touch_down(2, point(50.0, 50.0));
+        assert_eq!(
+            handler.on_touch_move(1, point(20.0, 0.0)),
+            TouchAction::None
+        );
+    }
+
+    #[test]
+    fn touch_cancel_drops_the_active_point() {
+        let mut handler = TouchHandler::new();
+        handler.on_touch_down(1, point(0.0, 0.0));
+        handler.on_touch_cancel(1);
+        assert_eq!(handler.on_touch_up(1, point(0.0, 0.0)), TouchAction::None);
+    }
+}